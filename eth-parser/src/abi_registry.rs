@@ -0,0 +1,346 @@
+use std::{collections::HashMap, fs::File, path::Path, str::FromStr};
+
+use kiln_postgres::DecodedParam;
+use log::info;
+use serde_json::{json, Value};
+use web3::{
+	ethabi::{Contract, Event, Function, ParamType, RawLog, Token},
+	types::{Log, H160, H256, U256},
+};
+
+use crate::Error;
+
+/// A function call or an event decoded with an ABI of the registry
+pub struct Decoded {
+	pub name: String,
+	/// Canonical signature, e.g. `transfer(address,uint256)`
+	pub signature: String,
+	pub params: Vec<DecodedParam>,
+}
+
+/// ABIs used to decode the transactions inputs and the logs, loaded from a directory
+///
+/// Every `.json` file of the directory holds a JSON ABI. A file named after a contract address,
+/// e.g. `0x00000000219ab540356cBB839Cbe05303d7705Fa.json`, only applies to that contract. Any
+/// other file applies to every contract, its functions matched by 4-byte selector and its events
+/// by topic.
+#[derive(Default)]
+pub struct AbiRegistry {
+	by_address: HashMap<H160, Contract>,
+	functions: HashMap<[u8; 4], Vec<Function>>,
+	events: HashMap<H256, Vec<Event>>,
+}
+
+impl AbiRegistry {
+	/// Load every ABI of `dir`
+	pub fn load(dir: &Path) -> Result<AbiRegistry, Error> {
+		let mut registry = AbiRegistry::default();
+		for entry in std::fs::read_dir(dir)? {
+			let path = entry?.path();
+			if path.extension().and_then(|e| e.to_str()) != Some("json") {
+				continue
+			}
+
+			let contract = Contract::load(File::open(&path)?)
+				.map_err(|e| Error::InvalidAbi(path.display().to_string(), e))?;
+			let address =
+				path.file_stem().and_then(|s| s.to_str()).and_then(|s| H160::from_str(s).ok());
+			registry.insert(address, contract);
+		}
+		info!(
+			"Loaded the ABIs of {} contracts, {} selectors and {} events from {}",
+			registry.by_address.len(),
+			registry.functions.len(),
+			registry.events.len(),
+			dir.display()
+		);
+
+		Ok(registry)
+	}
+
+	// Add the ABI of `address`, or of every contract if None
+	fn insert(&mut self, address: Option<H160>, contract: Contract) {
+		match address {
+			Some(address) => {
+				self.by_address.insert(address, contract);
+			},
+			None => {
+				for function in contract.functions() {
+					self.functions
+						.entry(function.short_signature())
+						.or_default()
+						.push(function.clone());
+				}
+				for event in contract.events().filter(|e| !e.anonymous) {
+					self.events.entry(event.signature()).or_default().push(event.clone());
+				}
+			},
+		}
+	}
+
+	/// Decode the input of a transaction sent to `contract`
+	///
+	/// The ABI of `contract` is preferred over the ones matched by selector. Return None if no
+	/// function of the registry can decode the input.
+	pub fn decode_input(&self, contract: H160, input: &[u8]) -> Option<Decoded> {
+		let selector: [u8; 4] = input.get(..4)?.try_into().ok()?;
+		let data = &input[4..];
+
+		let contract_functions = self
+			.by_address
+			.get(&contract)
+			.into_iter()
+			.flat_map(|c| c.functions())
+			.filter(|f| f.short_signature() == selector);
+		let selector_functions = self.functions.get(&selector).into_iter().flatten();
+
+		// Several functions may share a selector, keep the first one able to decode the input
+		contract_functions.chain(selector_functions).find_map(|f| {
+			let tokens = f.decode_input(data).ok()?;
+			let inputs: Vec<(&str, &ParamType)> =
+				f.inputs.iter().map(|i| (i.name.as_str(), &i.kind)).collect();
+			Some(Decoded {
+				name: f.name.clone(),
+				signature: signature(&f.name, &inputs),
+				params: decode_params(&inputs, tokens),
+			})
+		})
+	}
+
+	/// Decode a log
+	///
+	/// The ABI of the contract that emitted the log is preferred over the ones matched by topic.
+	/// Return None if no event of the registry can decode the log or if it was removed by a reorg.
+	pub fn decode_log(&self, log: &Log) -> Option<Decoded> {
+		if log.removed == Some(true) {
+			return None
+		}
+		let topic = log.topics.first()?;
+
+		let contract_events = self
+			.by_address
+			.get(&log.address)
+			.into_iter()
+			.flat_map(|c| c.events())
+			.filter(|e| !e.anonymous && e.signature() == *topic);
+		let topic_events = self.events.get(topic).into_iter().flatten();
+
+		// Events with the same signature may differ by their indexed parameters, e.g. ERC-20 and
+		// ERC-721 Transfer, keep the first one able to decode the log
+		contract_events.chain(topic_events).find_map(|e| {
+			let raw_log = RawLog {
+				topics: log.topics.clone(),
+				data: log.data.0.clone(),
+			};
+			let decoded = e.parse_log(raw_log).ok()?;
+			let inputs: Vec<(&str, &ParamType)> =
+				e.inputs.iter().map(|i| (i.name.as_str(), &i.kind)).collect();
+			Some(Decoded {
+				name: e.name.clone(),
+				signature: signature(&e.name, &inputs),
+				params: decode_params(
+					&inputs,
+					decoded.params.into_iter().map(|p| p.value).collect(),
+				),
+			})
+		})
+	}
+}
+
+// Return the canonical signature of a function or an event, from the names and types of its inputs
+fn signature(name: &str, inputs: &[(&str, &ParamType)]) -> String {
+	let types: Vec<String> = inputs.iter().map(|(_, kind)| kind.to_string()).collect();
+	format!("{}({})", name, types.join(","))
+}
+
+// Pair the decoded tokens with the parameters they were decoded for
+fn decode_params(inputs: &[(&str, &ParamType)], tokens: Vec<Token>) -> Vec<DecodedParam> {
+	inputs
+		.iter()
+		.zip(tokens)
+		.map(|((name, kind), t)| DecodedParam {
+			name: name.to_string(),
+			kind: kind.to_string(),
+			value: token_to_json(&t),
+		})
+		.collect()
+}
+
+// Convert a token to JSON
+//
+// Integers are written as decimal strings since they may not fit in a JSON number, bytes and
+// addresses as 0x prefixed hex strings
+fn token_to_json(token: &Token) -> Value {
+	match token {
+		Token::Address(a) => json!(format!("{a:?}")),
+		Token::FixedBytes(b) | Token::Bytes(b) => json!(format!("0x{}", hex::encode(b))),
+		// Signed integers are encoded in two's complement
+		Token::Int(i) if i.bit(255) => json!(format!("-{}", (!*i).overflowing_add(U256::one()).0)),
+		Token::Int(i) | Token::Uint(i) => json!(i.to_string()),
+		Token::Bool(b) => json!(b),
+		Token::String(s) => json!(s),
+		Token::FixedArray(tokens) | Token::Array(tokens) | Token::Tuple(tokens) =>
+			Value::Array(tokens.iter().map(token_to_json).collect()),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use serde_json::json;
+	use web3::{
+		ethabi::{Contract, Token},
+		types::{Bytes, Log, H160, H256, U256},
+	};
+
+	use super::{token_to_json, AbiRegistry};
+
+	// burn(uint256) and collate_propagate_storage(bytes16) share the selector 0x42966c68
+	const BURN_ABI: &str = r#"[{"type":"function","name":"burn","inputs":[{"name":"amount","type":"uint256"}],"outputs":[],"stateMutability":"nonpayable"}]"#;
+	const COLLATE_ABI: &str = r#"[{"type":"function","name":"collate_propagate_storage","inputs":[{"name":"","type":"bytes16"}],"outputs":[],"stateMutability":"nonpayable"}]"#;
+	const ERC20_TRANSFER_ABI: &str = r#"[{"type":"event","name":"Transfer","anonymous":false,"inputs":[{"name":"from","type":"address","indexed":true},{"name":"to","type":"address","indexed":true},{"name":"value","type":"uint256","indexed":false}]}]"#;
+	const ERC721_TRANSFER_ABI: &str = r#"[{"type":"event","name":"Transfer","anonymous":false,"inputs":[{"name":"from","type":"address","indexed":true},{"name":"to","type":"address","indexed":true},{"name":"tokenId","type":"uint256","indexed":true}]}]"#;
+
+	const SELECTOR: [u8; 4] = [0x42, 0x96, 0x6c, 0x68];
+
+	// Return a registry holding the ABIs of `contracts`, the ones without address apply to every
+	// contract
+	fn registry(contracts: &[(Option<H160>, &str)]) -> AbiRegistry {
+		let mut registry = AbiRegistry::default();
+		for (address, abi) in contracts {
+			registry.insert(*address, Contract::load(abi.as_bytes()).unwrap());
+		}
+		registry
+	}
+
+	// Return the input of a call to the colliding functions, with `word` as argument
+	fn colliding_input(word: u64) -> Vec<u8> {
+		let mut input = SELECTOR.to_vec();
+		input.extend_from_slice(H256::from_low_u64_be(word).as_bytes());
+		input
+	}
+
+	// Return a log emitted by a token contract
+	fn transfer_log(topics: Vec<H256>, data: Vec<u8>) -> Log {
+		Log {
+			address: H160::repeat_byte(0xaa),
+			topics,
+			data: Bytes(data),
+			block_hash: None,
+			block_number: None,
+			transaction_hash: None,
+			transaction_index: None,
+			log_index: None,
+			transaction_log_index: None,
+			log_type: None,
+			removed: None,
+		}
+	}
+
+	// Return the topic shared by the ERC-20 and ERC-721 Transfer events
+	fn transfer_topic() -> H256 {
+		Contract::load(ERC20_TRANSFER_ABI.as_bytes())
+			.unwrap()
+			.event("Transfer")
+			.unwrap()
+			.signature()
+	}
+
+	#[test]
+	fn negative_ints_are_decoded_from_twos_complement() {
+		assert_eq!(token_to_json(&Token::Int(U256::MAX)), json!("-1"));
+		assert_eq!(
+			token_to_json(&Token::Int(U256::MAX - U256::from(255))),
+			json!("-256")
+		);
+		assert_eq!(
+			token_to_json(&Token::Int(U256::one() << 255)),
+			json!(format!("-{}", U256::one() << 255))
+		);
+		assert_eq!(token_to_json(&Token::Int(U256::from(42))), json!("42"));
+	}
+
+	#[test]
+	fn uints_are_never_negative() {
+		assert_eq!(
+			token_to_json(&Token::Uint(U256::MAX)),
+			json!(U256::MAX.to_string())
+		);
+	}
+
+	#[test]
+	fn colliding_selectors_prefer_the_contract_abi() {
+		let contract = H160::repeat_byte(1);
+		let registry = registry(&[(Some(contract), BURN_ABI), (None, COLLATE_ABI)]);
+
+		let decoded = registry.decode_input(contract, &colliding_input(7)).unwrap();
+		assert_eq!(decoded.name, "burn");
+		assert_eq!(decoded.signature, "burn(uint256)");
+		assert_eq!(decoded.params[0].value, json!("7"));
+
+		let decoded = registry.decode_input(H160::repeat_byte(2), &colliding_input(7)).unwrap();
+		assert_eq!(decoded.signature, "collate_propagate_storage(bytes16)");
+	}
+
+	#[test]
+	fn colliding_selectors_keep_the_first_function_loaded() {
+		let registry = registry(&[(None, BURN_ABI), (None, COLLATE_ABI)]);
+
+		let decoded = registry.decode_input(H160::zero(), &colliding_input(7)).unwrap();
+		assert_eq!(decoded.signature, "burn(uint256)");
+	}
+
+	#[test]
+	fn truncated_input_is_not_decoded() {
+		let registry = registry(&[(None, BURN_ABI), (None, COLLATE_ABI)]);
+
+		assert!(registry.decode_input(H160::zero(), &SELECTOR).is_none());
+		assert!(registry.decode_input(H160::zero(), &SELECTOR[..2]).is_none());
+	}
+
+	#[test]
+	fn erc20_and_erc721_transfers_are_told_apart() {
+		let from = H256::from(H160::repeat_byte(0x11));
+		let to = H256::from(H160::repeat_byte(0x22));
+		let erc20_log = transfer_log(
+			vec![transfer_topic(), from, to],
+			H256::from_low_u64_be(1000).as_bytes().to_vec(),
+		);
+		let erc721_log = transfer_log(
+			vec![transfer_topic(), from, to, H256::from_low_u64_be(5)],
+			vec![],
+		);
+
+		// Whichever event is tried first
+		for abis in [
+			[(None, ERC20_TRANSFER_ABI), (None, ERC721_TRANSFER_ABI)],
+			[(None, ERC721_TRANSFER_ABI), (None, ERC20_TRANSFER_ABI)],
+		] {
+			let registry = registry(&abis);
+
+			let decoded = registry.decode_log(&erc20_log).unwrap();
+			assert_eq!(decoded.signature, "Transfer(address,address,uint256)");
+			assert_eq!(decoded.params[2].name, "value");
+			assert_eq!(decoded.params[2].value, json!("1000"));
+
+			let decoded = registry.decode_log(&erc721_log).unwrap();
+			assert_eq!(
+				decoded.params[0].value,
+				json!(format!("{:?}", H160::repeat_byte(0x11)))
+			);
+			assert_eq!(decoded.params[2].name, "tokenId");
+			assert_eq!(decoded.params[2].value, json!("5"));
+		}
+	}
+
+	#[test]
+	fn removed_logs_are_not_decoded() {
+		let registry = registry(&[(None, ERC721_TRANSFER_ABI)]);
+		let mut log = transfer_log(
+			vec![transfer_topic(), H256::zero(), H256::zero(), H256::zero()],
+			vec![],
+		);
+		log.removed = Some(true);
+
+		assert!(registry.decode_log(&log).is_none());
+	}
+}
@@ -1,8 +1,13 @@
-use clap::Parser;
+use std::{path::PathBuf, time::Duration};
+
+use clap::{Parser, Subcommand};
 
 #[derive(Parser, Debug)]
 #[clap(version, about)]
 pub struct Args {
+	#[clap(subcommand)]
+	command: Option<Command>,
+
 	/// Height from where the consensus layer will be synced
 	#[clap(long)]
 	from_slot: Option<u64>,
@@ -12,11 +17,68 @@ pub struct Args {
 	from_block: Option<u64>,
 
 	/// Height of the consensus slot at which the database will be fronzen
+	///
+	/// If not set, the chain head is followed until the process is stopped
+	#[clap(long)]
+	freeze_at: Option<u64>,
+
+	/// Seconds to wait between two polls of the chain head
+	#[clap(long, default_value = "12")]
+	poll_interval: u64,
+
+	/// Maximum number of slots fetched concurrently from the consensus node
+	#[clap(long, default_value = "8")]
+	consensus_concurrency: usize,
+
+	/// Maximum number of blocks fetched concurrently from the execution node
+	#[clap(long, default_value = "8")]
+	execution_concurrency: usize,
+
+	/// Store slots past the finalized checkpoint as provisional and replace them once finalized
 	#[clap(long)]
-	freeze_at: u64,
+	track_finality: bool,
+
+	/// Trace the internal calls of every transaction and store them along the contracts they
+	/// create
+	///
+	/// Requires an execution node exposing the `debug` namespace
+	#[clap(long)]
+	trace_calls: bool,
+
+	/// Directory of JSON ABIs used to decode the transactions inputs and the logs
+	///
+	/// A file named after a contract address only applies to that contract, any other file
+	/// applies to every contract by function selector and event topic. ABIs are loaded at startup
+	#[clap(long)]
+	abi_dir: Option<PathBuf>,
+}
+
+#[derive(Subcommand, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+	/// Sync again the heights missing between the lowest and highest stored entries, then exit
+	Repair,
+	/// Store the validator balances of every epoch between two epochs included, then exit
+	///
+	/// Requires a consensus node keeping the historical states
+	BackfillBalances {
+		/// First epoch to backfill
+		#[clap(long)]
+		from_epoch: u64,
+
+		/// Last epoch to backfill
+		#[clap(long)]
+		to_epoch: u64,
+	},
+	/// Match the calls to the deposit contract with the deposits processed by the beacon chain,
+	/// then exit
+	ReconcileDeposits,
 }
 
 impl Args {
+	pub fn command(&self) -> Option<Command> {
+		self.command
+	}
+
 	pub fn first_slot(&self) -> Option<u64> {
 		self.from_slot
 	}
@@ -25,7 +87,31 @@ impl Args {
 		self.from_block
 	}
 
-	pub fn freeze_at(&self) -> u64 {
+	pub fn freeze_at(&self) -> Option<u64> {
 		self.freeze_at
 	}
+
+	pub fn poll_interval(&self) -> Duration {
+		Duration::from_secs(self.poll_interval)
+	}
+
+	pub fn consensus_concurrency(&self) -> usize {
+		self.consensus_concurrency.max(1)
+	}
+
+	pub fn execution_concurrency(&self) -> usize {
+		self.execution_concurrency.max(1)
+	}
+
+	pub fn track_finality(&self) -> bool {
+		self.track_finality
+	}
+
+	pub fn trace_calls(&self) -> bool {
+		self.trace_calls
+	}
+
+	pub fn abi_dir(&self) -> Option<&PathBuf> {
+		self.abi_dir.as_ref()
+	}
 }
@@ -1,7 +1,10 @@
 use std::{env, time::Duration};
 
 use eth2::{
-	types::{ConfigAndPreset, MainnetEthSpec, SignedBeaconBlock, Slot, StateId, ValidatorData},
+	types::{
+		CommitteeData, ConfigAndPreset, EthSpec, MainnetEthSpec, SignedBeaconBlock, Slot, StateId,
+		ValidatorData,
+	},
 	BeaconNodeHttpClient, Timeouts,
 };
 use sensitive_url::SensitiveUrl;
@@ -31,6 +34,27 @@ pub async fn get_head_height(client: &BeaconNodeHttpClient) -> Result<u64, Error
 	Ok(ret.data.head_slot.as_u64())
 }
 
+/// Return the id of the first slot of the finalized checkpoint epoch
+///
+/// https://ethereum.github.io/beacon-APIs/#/Beacon/getStateFinalityCheckpoints response.finalized
+pub async fn get_finalized_height(client: &BeaconNodeHttpClient) -> Result<u64, Error> {
+	let opt_r = client.get_beacon_states_finality_checkpoints(StateId::Head).await?;
+	let opt_epoch = opt_r.map(|r| r.data.finalized.epoch);
+
+	Ok(opt_epoch.map_or(0, |e| {
+		e.start_slot(MainnetEthSpec::slots_per_epoch()).as_u64()
+	}))
+}
+
+/// Return the genesis time of the chain
+///
+/// https://ethereum.github.io/beacon-APIs/#/Beacon/getGenesis
+pub async fn get_genesis_time(client: &BeaconNodeHttpClient) -> Result<u64, Error> {
+	let r = client.get_beacon_genesis().await?;
+
+	Ok(r.data.genesis_time)
+}
+
 /// Return the list of validators at `slot`
 ///
 /// https://ethereum.github.io/beacon-APIs/#/Beacon/getStateValidators
@@ -44,6 +68,57 @@ pub async fn get_validators_at_slot(
 	Ok(opt_r.map(|r| r.data))
 }
 
+/// Return the committees assigned to attest at `slot`, read from the state at `state_slot`
+///
+/// The epoch of `slot` is requested explicitly, the node would otherwise default to the epoch of
+/// the state and return no committee for a slot of the previous epoch.
+///
+/// https://ethereum.github.io/beacon-APIs/#/Beacon/getEpochCommittees
+pub async fn get_committees(
+	client: &BeaconNodeHttpClient,
+	state_slot: u64,
+	slot: u64,
+) -> Result<Option<Vec<CommitteeData>>, Error> {
+	let state_id = StateId::Slot(Slot::new(state_slot));
+	let slot = Slot::new(slot);
+	let epoch = slot.epoch(MainnetEthSpec::slots_per_epoch());
+	let opt_r = client
+		.get_beacon_states_committees(state_id, Some(slot), None, Some(epoch))
+		.await?;
+
+	Ok(opt_r.map(|r| r.data))
+}
+
+/// Return the index of the validator expected to propose the block of `slot`
+///
+/// https://ethereum.github.io/beacon-APIs/#/Validator/getProposerDuties
+pub async fn get_proposer_at_slot(
+	client: &BeaconNodeHttpClient,
+	slot: u64,
+) -> Result<Option<u64>, Error> {
+	let slot = Slot::new(slot);
+	let r = client
+		.get_validator_duties_proposer(slot.epoch(MainnetEthSpec::slots_per_epoch()))
+		.await?;
+
+	Ok(r.data.iter().find(|d| d.slot == slot).map(|d| d.validator_index))
+}
+
+/// Return the indexes of the sync committee members at `slot`, ordered by position
+///
+/// Only available from the Altair fork
+///
+/// https://ethereum.github.io/beacon-APIs/#/Beacon/getEpochSyncCommittees
+pub async fn get_sync_committee_at_slot(
+	client: &BeaconNodeHttpClient,
+	slot: u64,
+) -> Result<Option<Vec<u64>>, Error> {
+	let state_id = StateId::Slot(Slot::new(slot));
+	let opt_r = client.get_beacon_states_sync_committees(state_id, None).await?;
+
+	Ok(opt_r.map(|r| r.data.validators))
+}
+
 /// Return the chain spec
 ///
 /// https://ethereum.github.io/beacon-APIs/#/Config/getSpec
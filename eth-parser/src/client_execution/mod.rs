@@ -1,9 +1,12 @@
 use std::env;
 
+use futures::future::try_join_all;
+use serde::{Deserialize, Serialize};
 use web3::{
-	transports::Http,
-	types::{Block, BlockId, BlockNumber, Transaction, TransactionReceipt, H256},
-	Web3,
+	helpers::{serialize, CallFuture},
+	transports::{Batch, Http},
+	types::{Block, BlockId, BlockNumber, Transaction, TransactionReceipt, H160, H256, U256},
+	Transport, Web3,
 };
 
 use crate::Error;
@@ -32,14 +35,80 @@ pub async fn get_block(
 	Ok(opt_r)
 }
 
-/// Get the receipt of transaction `hash`
+/// Maximum number of requests sent in a single JSON-RPC batch
+const RECEIPTS_PER_BATCH: usize = 100;
+
+/// Get the receipts of the transactions `hashes`, in the same order
+///
+/// Requests are sent in batches of `RECEIPTS_PER_BATCH`
 ///
 /// https://eth.wiki/json-rpc/API#eth_gettransactionreceipt
-pub async fn get_transaction_receipt(
+pub async fn get_transaction_receipts(
 	client: Web3<Http>,
-	hash: H256,
-) -> Result<Option<TransactionReceipt>, Error> {
-	let receip = client.eth().transaction_receipt(hash).await?;
+	hashes: &[H256],
+) -> Result<Vec<Option<TransactionReceipt>>, Error> {
+	let mut receipts = Vec::with_capacity(hashes.len());
+
+	for chunk in hashes.chunks(RECEIPTS_PER_BATCH) {
+		let batch = Web3::new(Batch::new(client.transport().clone()));
+		let pending: Vec<_> = chunk.iter().map(|h| batch.eth().transaction_receipt(*h)).collect();
+		batch.transport().submit_batch().await?;
+
+		receipts.extend(try_join_all(pending).await?);
+	}
+
+	Ok(receipts)
+}
+
+/// Get the runtime bytecode of the contract at `address`, as of the block at `height`
+///
+/// https://eth.wiki/json-rpc/API#eth_getcode
+pub async fn get_code(client: Web3<Http>, address: H160, height: u64) -> Result<Vec<u8>, Error> {
+	let code = client.eth().code(address, Some(BlockNumber::Number(height.into()))).await?;
+
+	Ok(code.0)
+}
+
+/// A call made during the execution of a transaction, as reported by the call tracer
+#[derive(Deserialize, Debug, Clone)]
+pub struct CallFrame {
+	/// Opcode of the call, e.g. `CALL`, `DELEGATECALL` or `CREATE2`
+	#[serde(rename = "type")]
+	pub call_type: String,
+	pub from: H160,
+	/// Created contract for a creation, None if the creation failed
+	pub to: Option<H160>,
+	pub value: Option<U256>,
+	pub error: Option<String>,
+	/// Calls made by the callee, in execution order
+	#[serde(default)]
+	pub calls: Vec<CallFrame>,
+}
+
+#[derive(Deserialize)]
+struct TransactionTrace {
+	result: CallFrame,
+}
+
+#[derive(Serialize)]
+struct TracerOptions {
+	tracer: &'static str,
+}
+
+/// Trace the calls made by every transaction of the block at `height`, in block order
+///
+/// Requires a node exposing the `debug` namespace
+///
+/// https://geth.ethereum.org/docs/rpc/ns-debug#debug_traceblockbynumber
+pub async fn trace_block(client: Web3<Http>, height: u64) -> Result<Vec<CallFrame>, Error> {
+	let params = vec![
+		serialize(&BlockNumber::Number(height.into())),
+		serialize(&TracerOptions {
+			tracer: "callTracer",
+		}),
+	];
+	let traces: Vec<TransactionTrace> =
+		CallFuture::new(client.transport().execute("debug_traceBlockByNumber", params)).await?;
 
-	Ok(receip)
+	Ok(traces.into_iter().map(|t| t.result).collect())
 }
@@ -1,5 +1,7 @@
 use std::{env::VarError, fmt::Display};
 
+use web3::ethabi;
+
 use sensitive_url::SensitiveError;
 use tokio::task::JoinError;
 
@@ -14,13 +16,16 @@ pub enum Error {
 	Join(JoinError),
 	Diesel(diesel::result::Error),
 	Sync(SyncError),
+	Io(std::io::Error),
+	/// An ABI file of the registry could not be parsed
+	InvalidAbi(String, ethabi::Error),
 	/// Chain preset not supported
 	InvalidChainPreset(String),
 	/// Config name is missing from chain config
 	MissingChainName,
-	/// Config name invalid
-	InvalidChainName,
-	/// Cannot use a pre merge slot (height < 29151) as freeze slot
+	/// The node deposit contract doesn't match the one of the known network
+	DepositContractMismatch(String),
+	/// Cannot use a pre merge slot as freeze slot
 	PreMergeFreezeSlot,
 }
 
@@ -66,6 +71,12 @@ impl From<SyncError> for Error {
 	}
 }
 
+impl From<std::io::Error> for Error {
+	fn from(error: std::io::Error) -> Self {
+		Error::Io(error)
+	}
+}
+
 impl Display for Error {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 		match self {
@@ -75,6 +86,12 @@ impl Display for Error {
 				p
 			),
 			Self::MissingChainName => write!(f, "Invalid config. 'config_name' is required."),
+			Self::DepositContractMismatch(name) => write!(
+				f,
+				"The node deposit contract doesn't match the one of the '{}' network",
+				name
+			),
+			Self::InvalidAbi(file, e) => write!(f, "Invalid ABI file '{}': {}", file, e),
 			_ => write!(f, "{:?}", self),
 		}
 	}
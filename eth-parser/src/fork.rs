@@ -0,0 +1,114 @@
+use eth2::{
+	types::{MainnetEthSpec, SignedBeaconBlock},
+	BeaconNodeHttpClient,
+};
+use log::info;
+
+use crate::{client_consensus, Error};
+
+/// Return the first slot whose block carries a non empty execution payload
+///
+/// Blocks between the Bellatrix fork and the terminal block carry an empty payload, all the
+/// following ones carry a non empty one. The merge slot is found by binary search between the
+/// fork slot and `head`, instead of querying every slot.
+///
+/// Return None if the merge did not happen yet.
+pub async fn find_merge_slot(
+	client: &BeaconNodeHttpClient,
+	bellatrix_fork_slot: Option<u64>,
+	head: u64,
+) -> Result<Option<u64>, Error> {
+	let mut low = match bellatrix_fork_slot {
+		Some(slot) if slot <= head => slot,
+		_ => return Ok(None),
+	};
+
+	// `high` is always the slot of a merged block
+	let mut high = match find_block_backward(client, head, low).await? {
+		Some((slot, true)) => slot,
+		_ => return Ok(None),
+	};
+
+	while low < high {
+		let mid = low + (high - low) / 2;
+		info!("looking for the merge slot between {low} and {high}");
+
+		match find_block_forward(client, mid, high).await? {
+			Some((slot, true)) => high = slot,
+			Some((slot, false)) => low = slot + 1,
+			// Unreachable as long as `high` is the slot of a block
+			None => break,
+		}
+	}
+
+	Ok(Some(high))
+}
+
+/// Return the number of the execution block of the last slot up to `height` with a payload
+///
+/// Return 0 if there is no execution block before `height`.
+pub async fn find_last_exec_block(
+	client: &BeaconNodeHttpClient,
+	merge_slot: Option<u64>,
+	height: u64,
+) -> Result<u64, Error> {
+	let merge_slot = match merge_slot {
+		Some(slot) if slot <= height => slot,
+		_ => return Ok(0),
+	};
+
+	// Every block after the merge has a payload, only missed slots are skipped
+	for h in (merge_slot..=height).rev() {
+		info!("looking for execution payload in slot {h}");
+		let block = match client_consensus::get_block(client, h).await? {
+			Some(b) => b,
+			None => continue,
+		};
+		let opt_block_number =
+			block.message().body().execution_payload().ok().map(|p| p.block_number);
+		if let Some(n) = opt_block_number {
+			return Ok(n)
+		}
+	}
+
+	Ok(0)
+}
+
+// Return the slot of the first block between `from` and `to` included, and whether it is merged
+async fn find_block_forward(
+	client: &BeaconNodeHttpClient,
+	from: u64,
+	to: u64,
+) -> Result<Option<(u64, bool)>, Error> {
+	for h in from..=to {
+		if let Some(block) = client_consensus::get_block(client, h).await? {
+			return Ok(Some((h, is_merged(&block))))
+		}
+	}
+
+	Ok(None)
+}
+
+// Return the slot of the last block between `to` and `from` included, and whether it is merged
+async fn find_block_backward(
+	client: &BeaconNodeHttpClient,
+	from: u64,
+	to: u64,
+) -> Result<Option<(u64, bool)>, Error> {
+	for h in (to..=from).rev() {
+		if let Some(block) = client_consensus::get_block(client, h).await? {
+			return Ok(Some((h, is_merged(&block))))
+		}
+	}
+
+	Ok(None)
+}
+
+// Return true if the block carries a non empty execution payload
+fn is_merged(block: &SignedBeaconBlock<MainnetEthSpec>) -> bool {
+	block
+		.message()
+		.body()
+		.execution_payload()
+		.map_or(false, |p| !p.block_hash.into_root().is_zero())
+}
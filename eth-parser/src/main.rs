@@ -1,24 +1,39 @@
 #[macro_use]
 extern crate lazy_static;
 
+mod abi_registry;
 mod args;
 mod client_consensus;
 mod client_execution;
 mod error;
+mod fork;
+mod network;
+mod shutdown;
 mod sync;
 
-use args::Args;
+use std::sync::Arc;
+
+use abi_registry::AbiRegistry;
+use args::{Args, Command};
 use clap::StructOpt;
 use dotenv::dotenv;
 use error::*;
 use eth2::BeaconNodeHttpClient;
-use log::info;
-use sync::validators::update_validators;
+use kiln_postgres::ValidatorBalance;
+use log::{info, warn};
+use network::Network;
+use shutdown::Shutdown;
+use sync::{
+	deposits::reconcile_deposits,
+	validators::{epoch_of_slot, record_balances, update_validators},
+};
 use tokio::join;
 
 use crate::sync::{ConsensusSyncer, DbSyncer, ExecutionSyncer};
 
-const FIRST_SLOT_WITH_EXEC_BLOCK: u64 = 29151;
+/// Maximum number of epochs whose validator balances are recorded at once while following the
+/// chain
+const MAX_BALANCES_CATCH_UP: u64 = 4;
 
 #[tokio::main]
 async fn main() -> Result<(), Error> {
@@ -26,71 +41,216 @@ async fn main() -> Result<(), Error> {
 	env_logger::init();
 	let args = Args::parse();
 
-	if args.freeze_at() < FIRST_SLOT_WITH_EXEC_BLOCK {
-		return Err(Error::PreMergeFreezeSlot)
-	}
+	let shutdown = Shutdown::listen();
 
 	let conn_pool = kiln_postgres::connexion_pool();
 	let eth2 = client_consensus::new_client()?;
 	let web3 = client_execution::new_client()?;
 
+	// ABIs are loaded once, adding a contract only requires a restart
+	let abi_registry = Arc::new(match args.abi_dir() {
+		Some(dir) => AbiRegistry::load(dir)?,
+		None => AbiRegistry::default(),
+	});
+
 	let spec = client_consensus::get_config_spec(&eth2).await?;
 	let config = spec.config;
 	// Currently we only handle the mainet preset
 	if config.preset_base != "mainnet" {
 		return Err(Error::InvalidChainPreset(config.preset_base))
 	}
-	// Handling multiple chains would add a lot of complexity in database (each block must reference
-	// the chain it's related to). Only handling Kiln is all we need right now
-	match config.config_name {
-		Some(name) if name != "kiln" => return Err(Error::InvalidChainName),
-		None => return Err(Error::MissingChainName),
+	// Every row is stored along the name of the network it belongs to
+	let genesis_time = client_consensus::get_genesis_time(&eth2).await?;
+	let network = Network::from_node_config(&config, genesis_time)?;
+	info!(
+		"Indexing the '{}' network, genesis time {}",
+		network.name(),
+		network.genesis_time()
+	);
+
+	// Locate the first slot with an execution payload from the Bellatrix fork epoch
+	let head = client_consensus::get_head_height(&eth2).await?;
+	let mut merge_slot = fork::find_merge_slot(&eth2, network.bellatrix_fork_slot(), head).await?;
+	info!(
+		"Bellatrix fork at slot {:?}, terminal total difficulty {}, merge at slot {:?}",
+		network.bellatrix_fork_slot(),
+		config.terminal_total_difficulty,
+		merge_slot
+	);
+
+	match (args.freeze_at(), merge_slot) {
+		(Some(freeze_at), Some(merge_slot)) if freeze_at < merge_slot =>
+			return Err(Error::PreMergeFreezeSlot),
+		(Some(_), None) => return Err(Error::PreMergeFreezeSlot),
 		_ => {},
 	}
 
+	if args.command() == Some(Command::Repair) {
+		// Refilled slots get the same finality status as the slots stored while following
+		let finalized_height = get_finalized_height(&eth2, args.track_finality()).await?;
+		let consensus_syncer = ConsensusSyncer::new(
+			conn_pool.clone(),
+			eth2.clone(),
+			network.clone(),
+			finalized_height,
+			args.consensus_concurrency(),
+		);
+		let execution_syncer = ExecutionSyncer::new(
+			conn_pool.clone(),
+			web3.clone(),
+			network.clone(),
+			args.execution_concurrency(),
+			args.trace_calls(),
+			abi_registry.clone(),
+		);
+
+		let (res_consensus, res_execution) = join!(
+			consensus_syncer.repair(&shutdown),
+			execution_syncer.repair(&shutdown)
+		);
+		info!(
+			"Repaired {} slots and {} blocks",
+			res_consensus?, res_execution?
+		);
+
+		return Ok(())
+	}
+
+	if let Some(Command::BackfillBalances {
+		from_epoch,
+		to_epoch,
+	}) = args.command()
+	{
+		for epoch in from_epoch..=to_epoch {
+			if shutdown.is_requested() {
+				break
+			}
+			let recorded = record_balances(conn_pool.clone(), &eth2, network.name(), epoch).await?;
+			info!("Recorded {recorded} validator balances for epoch {epoch}");
+		}
+
+		return Ok(())
+	}
+
+	if args.command() == Some(Command::ReconcileDeposits) {
+		let not_processed = reconcile_deposits(conn_pool.clone(), &network)?;
+		info!("{not_processed} deposits were not processed by the beacon chain");
+
+		return Ok(())
+	}
+
 	let mut consensus_height: u64;
+	// Starting heights only apply to the first iteration
+	let mut first_slot = args.first_slot();
+	let mut first_block = args.first_block();
+	// Balances are snapshotted once per epoch
+	let mut balances_epoch =
+		ValidatorBalance::get_highest_epoch(&conn_pool.get().unwrap(), network.name())?;
 
 	// Sync db with chain height
-	// Will loop until heigh rejoin `freeze_at`
+	// Will loop until heigh rejoin `freeze_at`, or follow the head until shutdown if not set
 	loop {
 		consensus_height = client_consensus::get_head_height(&eth2).await?;
-		let max_consensus_height = std::cmp::min(consensus_height, args.freeze_at());
+		let max_consensus_height = match args.freeze_at() {
+			Some(freeze_at) => std::cmp::min(consensus_height, freeze_at),
+			None => consensus_height,
+		};
+
+		update_validators(
+			conn_pool.clone(),
+			&eth2,
+			network.name(),
+			max_consensus_height,
+		)
+		.await?;
 
-		update_validators(conn_pool.clone(), &eth2, max_consensus_height).await?;
+		// Every epoch reached since the last snapshot is recorded, a long bump spans several.
+		// After a long downtime only the last ones are, the others are left to a backfill
+		let epoch = epoch_of_slot(max_consensus_height);
+		let mut first_epoch = balances_epoch.map_or(epoch, |e| e + 1);
+		if epoch.saturating_sub(first_epoch) >= MAX_BALANCES_CATCH_UP {
+			let skipped_to = epoch - MAX_BALANCES_CATCH_UP;
+			warn!(
+				"Skipping the validator balances of epochs {first_epoch} to {skipped_to}, run \
+				 backfill-balances --from-epoch {first_epoch} --to-epoch {skipped_to} to record them"
+			);
+			first_epoch = skipped_to + 1;
+		}
+		for epoch in first_epoch..=epoch {
+			if shutdown.is_requested() {
+				break
+			}
+			match record_balances(conn_pool.clone(), &eth2, network.name(), epoch).await {
+				Ok(recorded) => info!("Recorded {recorded} validator balances for epoch {epoch}"),
+				Err(err) => warn!(
+					"Failed to record the validator balances of epoch {epoch}, run \
+					 backfill-balances --from-epoch {epoch} --to-epoch {epoch} to retry: {err}"
+				),
+			}
+			balances_epoch = Some(epoch);
+		}
+
+		// The merge may happen while following the head
+		if merge_slot.is_none() {
+			merge_slot =
+				fork::find_merge_slot(&eth2, network.bellatrix_fork_slot(), max_consensus_height)
+					.await?;
+		}
+		let max_exec_height =
+			fork::find_last_exec_block(&eth2, merge_slot, max_consensus_height).await?;
 
-		let max_exec_height = find_last_exec_block(&eth2, max_consensus_height).await?;
+		// Slots past the finalized checkpoint are stored as provisional
+		let finalized_height = get_finalized_height(&eth2, args.track_finality()).await?;
 
-		let consensus_syncer = ConsensusSyncer::new(conn_pool.clone(), eth2.clone());
-		let execution_syncer = ExecutionSyncer::new(conn_pool.clone(), web3.clone());
+		let consensus_syncer = ConsensusSyncer::new(
+			conn_pool.clone(),
+			eth2.clone(),
+			network.clone(),
+			finalized_height,
+			args.consensus_concurrency(),
+		);
+		let promoted = consensus_syncer.promote_finalized().await?;
+		if promoted > 0 {
+			info!("{promoted} provisional slots are now finalized");
+		}
+		let execution_syncer = ExecutionSyncer::new(
+			conn_pool.clone(),
+			web3.clone(),
+			network.clone(),
+			args.execution_concurrency(),
+			args.trace_calls(),
+			abi_registry.clone(),
+		);
 
 		let (res_consensus, res_execution) = join!(
-			consensus_syncer.bump(args.first_slot().take(), max_consensus_height),
-			execution_syncer.bump(args.first_block().take(), max_exec_height),
+			consensus_syncer.bump(first_slot.take(), max_consensus_height, &shutdown),
+			execution_syncer.bump(first_block.take(), max_exec_height, &shutdown),
 		);
 		res_execution?;
-		if res_consensus? == args.freeze_at() {
+		if Some(res_consensus?) == args.freeze_at() {
+			break
+		}
+
+		// Wait for the head to move forward
+		shutdown.sleep(args.poll_interval()).await;
+		if shutdown.is_requested() {
 			break
 		}
 	}
 
+	info!("Database synced, exiting");
+
 	Ok(())
 }
 
-// Query consensus layer for slot between `height` and 0 until it find one with a non None
-// execution_payload
-async fn find_last_exec_block(eth2: &BeaconNodeHttpClient, height: u64) -> Result<u64, Error> {
-	for h in (0..height + 1).rev() {
-		info!("looking for execution payload in slot {h}");
-		let slot = match client_consensus::get_block(eth2, h).await? {
-			Some(s) => s,
-			None => continue,
-		};
-		let opt_block_number =
-			slot.message().body().execution_payload().ok().map(|p| p.block_number);
-		if let Some(n) = opt_block_number {
-			return Ok(n)
-		}
+/// Return the height of the finalized checkpoint if finality is tracked
+async fn get_finalized_height(
+	eth2: &BeaconNodeHttpClient,
+	track_finality: bool,
+) -> Result<Option<u64>, Error> {
+	if !track_finality {
+		return Ok(None)
 	}
 
-	Ok(0)
+	Ok(Some(client_consensus::get_finalized_height(eth2).await?))
 }
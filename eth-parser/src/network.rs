@@ -0,0 +1,129 @@
+use eth2::types::{Config, EthSpec, MainnetEthSpec};
+use web3::types::H160;
+
+use crate::Error;
+
+/// A network known by the indexer
+struct KnownNetwork {
+	/// Names the beacon node may report as `config_name`
+	names: &'static [&'static str],
+	deposit_contract_address: [u8; 20],
+}
+
+const KNOWN_NETWORKS: [KnownNetwork; 4] = [
+	// https://etherscan.io/address/0x00000000219ab540356cBB839Cbe05303d7705Fa
+	KnownNetwork {
+		names: &["mainnet"],
+		deposit_contract_address: [
+			0x00, 0x00, 0x00, 0x00, 0x21, 0x9a, 0xb5, 0x40, 0x35, 0x6c, 0xbb, 0x83, 0x9c, 0xbe,
+			0x05, 0x30, 0x3d, 0x77, 0x05, 0xfa,
+		],
+	},
+	// https://goerli.etherscan.io/address/0xff50ed3d0ec03aC01D4C79aAd74928BFF48a7b2b
+	KnownNetwork {
+		names: &["prater", "goerli"],
+		deposit_contract_address: [
+			0xff, 0x50, 0xed, 0x3d, 0x0e, 0xc0, 0x3a, 0xc0, 0x1d, 0x4c, 0x79, 0xaa, 0xd7, 0x49,
+			0x28, 0xbf, 0xf4, 0x8a, 0x7b, 0x2b,
+		],
+	},
+	// https://sepolia.etherscan.io/address/0x7f02C3E3c98b133055B8B348B2Ac625669Ed295D
+	KnownNetwork {
+		names: &["sepolia"],
+		deposit_contract_address: [
+			0x7f, 0x02, 0xc3, 0xe3, 0xc9, 0x8b, 0x13, 0x30, 0x55, 0xb8, 0xb3, 0x48, 0xb2, 0xac,
+			0x62, 0x56, 0x69, 0xed, 0x29, 0x5d,
+		],
+	},
+	// https://explorer.kiln.themerge.dev/address/0x4242424242424242424242424242424242424242/transactions
+	KnownNetwork {
+		names: &["kiln"],
+		deposit_contract_address: [0x42; 20],
+	},
+];
+
+/// The network followed by the indexer
+///
+/// Its name is stored on every row, so that several networks can share the same database.
+#[derive(Debug, Clone)]
+pub struct Network {
+	name: String,
+	deposit_contract_address: H160,
+	genesis_time: u64,
+	bellatrix_fork_slot: Option<u64>,
+}
+
+impl Network {
+	/// Return the network described by the beacon node config
+	///
+	/// Known networks must match the registry, any other network is considered a custom devnet and
+	/// trusted as described by the node.
+	pub fn from_node_config(config: &Config, genesis_time: u64) -> Result<Network, Error> {
+		let name = config.config_name.clone().ok_or(Error::MissingChainName)?;
+		let node_deposit_contract = H160::from_slice(config.deposit_contract_address.as_bytes());
+
+		let bellatrix_fork_slot = config
+			.bellatrix_fork_epoch
+			.as_ref()
+			.map(|e| e.value.start_slot(MainnetEthSpec::slots_per_epoch()).as_u64());
+
+		let lowercase_name = name.to_lowercase();
+		let opt_known = KNOWN_NETWORKS.iter().find(|n| n.names.contains(&lowercase_name.as_str()));
+
+		let network = match opt_known {
+			Some(known) => {
+				let deposit_contract_address = H160::from(known.deposit_contract_address);
+				if deposit_contract_address != node_deposit_contract {
+					return Err(Error::DepositContractMismatch(name))
+				}
+
+				Network {
+					// Aliases are all stored under the same name
+					name: known.names[0].to_string(),
+					deposit_contract_address,
+					genesis_time,
+					bellatrix_fork_slot,
+				}
+			},
+			None => Network {
+				name,
+				deposit_contract_address: node_deposit_contract,
+				genesis_time,
+				bellatrix_fork_slot,
+			},
+		};
+
+		Ok(network)
+	}
+
+	/// Return a custom devnet named `name`, without deposit contract nor Bellatrix fork
+	#[cfg(test)]
+	pub fn devnet(name: &str) -> Network {
+		Network {
+			name: name.to_string(),
+			deposit_contract_address: H160::zero(),
+			genesis_time: 0,
+			bellatrix_fork_slot: None,
+		}
+	}
+
+	/// Return the name stored on every row of this network
+	pub fn name(&self) -> &str {
+		&self.name
+	}
+
+	/// Return the address of the deposit contract on the execution layer
+	pub fn deposit_contract_address(&self) -> H160 {
+		self.deposit_contract_address
+	}
+
+	/// Return the genesis time as reported by the beacon node
+	pub fn genesis_time(&self) -> u64 {
+		self.genesis_time
+	}
+
+	/// Return the first slot of the Bellatrix fork, if scheduled
+	pub fn bellatrix_fork_slot(&self) -> Option<u64> {
+		self.bellatrix_fork_slot
+	}
+}
@@ -0,0 +1,75 @@
+use std::{
+	sync::{
+		atomic::{AtomicBool, Ordering},
+		Arc,
+	},
+	time::Duration,
+};
+
+use log::{error, info};
+use tokio::{
+	signal::unix::{signal, SignalKind},
+	sync::Notify,
+};
+
+/// Flag raised once the process received SIGTERM or SIGINT
+///
+/// Syncers check it between two heights so that the entry being written is always completed
+/// before the process exits.
+#[derive(Clone, Default)]
+pub struct Shutdown {
+	requested: Arc<AtomicBool>,
+	notify: Arc<Notify>,
+}
+
+impl Shutdown {
+	/// Return a new flag, raised by a background task listening to termination signals
+	pub fn listen() -> Shutdown {
+		let shutdown = Shutdown::default();
+
+		let flag = shutdown.clone();
+		tokio::spawn(async move {
+			let (mut sigterm, mut sigint) = match (
+				signal(SignalKind::terminate()),
+				signal(SignalKind::interrupt()),
+			) {
+				(Ok(term), Ok(int)) => (term, int),
+				_ => {
+					error!("Failed to listen to termination signals");
+					return
+				},
+			};
+
+			tokio::select! {
+				_ = sigterm.recv() => info!("SIGTERM received, shutting down gracefully"),
+				_ = sigint.recv() => info!("SIGINT received, shutting down gracefully"),
+			}
+
+			flag.requested.store(true, Ordering::SeqCst);
+			// Unlike `notify_waiters`, a permit is stored if the sleeper is not polled yet, so a
+			// signal received right after `is_requested` was checked still wakes it up
+			flag.notify.notify_one();
+		});
+
+		shutdown
+	}
+
+	/// Return true if a termination signal was received
+	pub fn is_requested(&self) -> bool {
+		self.requested.load(Ordering::SeqCst)
+	}
+
+	/// Sleep for `duration` or until a termination signal is received
+	///
+	/// A single task is expected to sleep at a time, only one of them would be woken up.
+	pub async fn sleep(&self, duration: Duration) {
+		if self.is_requested() {
+			return
+		}
+
+		tokio::select! {
+			_ = tokio::time::sleep(duration) => {},
+			_ = self.notify.notified() => {},
+		}
+	}
+}
@@ -1,22 +1,108 @@
-use std::fmt::Display;
+use std::{collections::BTreeSet, fmt::Display, ops::RangeInclusive};
 
 use async_trait::async_trait;
-use eth2::BeaconNodeHttpClient;
-use kiln_postgres::{NewSlot, PgConnectionPool, Slot};
+use diesel::{Connection, OptionalExtension, PgConnection};
+use eth2::{
+	types::{CommitteeData, Deposit, MainnetEthSpec, SignedBeaconBlock},
+	BeaconNodeHttpClient,
+};
+use kiln_postgres::{
+	ConsensusDeposit, ExecutionDeposit, NewAttestationDuties, NewAttestationDuty,
+	NewAttestationInclusion, NewAttestationInclusions, NewConsensusDeposit, NewConsensusDeposits,
+	NewSlashing, NewSlashings, NewSlot, NewSyncCommitteeMember, NewSyncCommitteeMembers,
+	NewSyncParticipation, NewSyncParticipations, NewVoluntaryExit, NewVoluntaryExits,
+	PgConnectionPool, SlashingType, Slot, SyncCommitteeMember,
+};
 use log::info;
 
-use super::syncer::DbSyncer;
+use super::{
+	syncer::DbSyncer,
+	validators::{epoch_of_slot, sync_committee_period_of_slot},
+	SyncError,
+};
 
-use crate::{client_consensus, Error};
+use crate::{client_consensus, network::Network, Error};
 
-pub(crate) struct ConsensusSyncer(PgConnectionPool, BeaconNodeHttpClient);
+/// Store consensus slots in database
+///
+/// When a finalized height is provided, slots above it are stored as provisional and are
+/// expected to be replaced by `promote_finalized` once finality passes them.
+/// Otherwise slots are stored without any finality status, and are never promoted.
+pub(crate) struct ConsensusSyncer {
+	conn_pool: PgConnectionPool,
+	client: BeaconNodeHttpClient,
+	network: Network,
+	finalized_height: Option<u64>,
+	max_in_flight: usize,
+}
 
 impl ConsensusSyncer {
 	pub fn new(
 		pg_connection: PgConnectionPool,
 		client_consensus: BeaconNodeHttpClient,
+		network: Network,
+		finalized_height: Option<u64>,
+		max_in_flight: usize,
 	) -> ConsensusSyncer {
-		ConsensusSyncer(pg_connection, client_consensus)
+		ConsensusSyncer {
+			conn_pool: pg_connection,
+			client: client_consensus,
+			network,
+			finalized_height,
+			max_in_flight,
+		}
+	}
+
+	/// Replace the provisional slots that are now behind the finalized checkpoint
+	///
+	/// Each of them is fetched again from the node, so that a block orphaned before finality is
+	/// replaced by the canonical one, or marked as missed if the slot ended up empty.
+	///
+	/// Return the number of promoted slots
+	pub async fn promote_finalized(&self) -> Result<usize, Error> {
+		let finalized_height = match self.finalized_height {
+			Some(h) => h,
+			None => return Ok(0),
+		};
+
+		let provisional_slots = Slot::list_provisional_up_to(
+			&self.conn_pool.get().unwrap(),
+			self.chain(),
+			finalized_height,
+		)?;
+		let count = provisional_slots.len();
+
+		for slot in provisional_slots {
+			let height = slot.height();
+			let entry = self.fetch_entry(height).await?;
+			let new_slot = match &entry.block {
+				Some(block) => new_slot_from_block(self.chain(), height, block, Some(true)),
+				None => NewSlot::missed(self.chain(), height, entry.missed_proposer, Some(true)),
+			};
+
+			let conn = self.conn_pool.get().unwrap();
+			if new_slot.block_root() == slot.block_root() {
+				new_slot.upsert(&conn)?;
+				continue
+			}
+
+			// The content of the orphaned block is replaced by the one of the canonical block
+			info!("{self}: Provisional slot {height} was replaced before finality");
+			conn.transaction::<_, Error, _>(|| {
+				new_slot.upsert(&conn)?;
+				delete_block_content(&conn, self.chain(), height)?;
+				store_block_content(&conn, self.chain(), height, &entry)?;
+
+				Ok(())
+			})?;
+		}
+
+		Ok(count)
+	}
+
+	// Return true if `height` is behind the finalized checkpoint, None if finality is not tracked
+	fn is_finalized(&self, height: u64) -> Option<bool> {
+		self.finalized_height.map(|finalized_height| height <= finalized_height)
 	}
 }
 
@@ -26,46 +112,490 @@ impl Display for ConsensusSyncer {
 	}
 }
 
+/// A slot fetched from the consensus node
+pub(crate) struct ConsensusEntry {
+	/// None if the slot was missed
+	block: Option<SignedBeaconBlock<MainnetEthSpec>>,
+	/// Validator that was expected to propose the missed block
+	missed_proposer: Option<u64>,
+	/// Committees assigned to attest at the slot
+	duties: Vec<CommitteeData>,
+	/// Committees of the slots attested by the attestations of the block
+	attested_committees: Vec<CommitteeData>,
+	/// Sync committee that signed the sync aggregate of the block, None before Altair
+	sync_committee: Option<SyncCommittee>,
+}
+
+/// Members of the sync committee of a period
+struct SyncCommittee {
+	period: u64,
+	/// Validator indexes, ordered by position
+	members: Vec<u64>,
+	/// False if the members were fetched from the node and must be stored
+	stored: bool,
+}
+
 #[async_trait]
 impl DbSyncer for ConsensusSyncer {
+	type Entry = ConsensusEntry;
 	type NodeClient = BeaconNodeHttpClient;
 
+	fn conn_pool(&self) -> &PgConnectionPool {
+		&self.conn_pool
+	}
+
+	fn chain(&self) -> &str {
+		self.network.name()
+	}
+
 	fn node_client(&self) -> Self::NodeClient {
-		self.1.clone()
+		self.client.clone()
+	}
+
+	fn max_in_flight(&self) -> usize {
+		self.max_in_flight
 	}
 
 	fn get_db_height(&self) -> Result<u64, Error> {
-		let highest_slot = Slot::get_highest(&self.0.get().unwrap())?;
+		let highest_slot = Slot::get_highest(&self.conn_pool.get().unwrap(), self.chain())?;
 
 		Ok(highest_slot.height())
 	}
 
-	async fn create_new_entry(&self, height: u64) -> Result<(), Error> {
-		// Fetch block
-		let opt_block = client_consensus::get_block(&self.node_client(), height).await?;
-		let block = match opt_block {
-			Some(b) => b,
+	fn list_missing_ranges(&self) -> Result<Vec<RangeInclusive<u64>>, Error> {
+		let ranges = Slot::list_missing_ranges(&self.conn_pool.get().unwrap(), self.chain())?;
+
+		Ok(ranges)
+	}
+
+	async fn fetch_entry(&self, height: u64) -> Result<Self::Entry, Error> {
+		let client = self.node_client();
+		let block = client_consensus::get_block(&client, height).await?;
+
+		// Attribute the missed proposal to the validator on duty
+		let missed_proposer = match block {
+			Some(_) => None,
+			None => client_consensus::get_proposer_at_slot(&client, height).await?,
+		};
+
+		// Committees exist even if the slot was missed
+		let duties = client_consensus::get_committees(&client, height, height)
+			.await?
+			.ok_or(SyncError::NoCommittees(height))?;
+
+		// Attestations are mapped to validators through the committees of the slot they attest
+		let mut attested_committees = vec![];
+		if let Some(block) = &block {
+			let attested_slots: BTreeSet<u64> = block
+				.message()
+				.body()
+				.attestations()
+				.iter()
+				.map(|a| a.data.slot.as_u64())
+				.collect();
+			for slot in attested_slots {
+				let committees = client_consensus::get_committees(&client, height, slot)
+					.await?
+					.ok_or(SyncError::NoCommittees(slot))?;
+				attested_committees.extend(committees);
+			}
+		}
+
+		// Sync committees are fetched from the node once per period
+		let mut sync_committee = None;
+		if let Some(Ok(_)) = block.as_ref().map(|b| b.message().body().sync_aggregate()) {
+			let period = sync_committee_period_of_slot(height);
+			let stored_members =
+				SyncCommitteeMember::list(&self.conn_pool.get().unwrap(), self.chain(), period)?;
+			sync_committee = Some(if stored_members.is_empty() {
+				SyncCommittee {
+					period,
+					members: client_consensus::get_sync_committee_at_slot(&client, height)
+						.await?
+						.ok_or(SyncError::NoSyncCommittee(height))?,
+					stored: false,
+				}
+			} else {
+				SyncCommittee {
+					period,
+					members: stored_members.iter().map(|m| m.validator_index()).collect(),
+					stored: true,
+				}
+			});
+		}
+
+		Ok(ConsensusEntry {
+			block,
+			missed_proposer,
+			duties,
+			attested_committees,
+			sync_committee,
+		})
+	}
+
+	async fn store_entry(&self, height: u64, entry: Self::Entry) -> Result<(), Error> {
+		let new_slot = match &entry.block {
+			Some(block) =>
+				new_slot_from_block(self.chain(), height, block, self.is_finalized(height)),
 			None => {
-				info!("Slot {height} was missed");
-				return Ok(())
+				info!(
+					"Slot {height} was missed by validator {:?}",
+					entry.missed_proposer
+				);
+				// Store missed slots so they can be told apart from unsynced ones
+				NewSlot::missed(
+					self.chain(),
+					height,
+					entry.missed_proposer,
+					self.is_finalized(height),
+				)
 			},
 		};
 
-		// Retrieve block hash and block number from the block
-		let block_hash = block
-			.message()
-			.body()
-			.execution_payload()
-			.ok()
-			.map(|p| p.block_hash.into_root());
-		let block_number = block.message().body().execution_payload().ok().map(|p| p.block_number);
-
-		// Create a new slot
-		let new_slot = NewSlot::new(height, block_hash, block_number);
+		// Write the slot along the content of its block
+		let conn = self.conn_pool.get().unwrap();
+		conn.transaction::<_, Error, _>(|| {
+			new_slot.insert_do_nothing(&conn)?;
+			store_block_content(&conn, self.chain(), height, &entry)?;
 
-		// Write the new slot in database
-		new_slot.insert_do_nothing(&self.0.get().unwrap())?;
+			Ok(())
+		})?;
 
 		Ok(())
 	}
 }
+
+// Store the attestations, slashings, exits, sync committee participations and deposits of the
+// entry
+fn store_block_content(
+	conn: &PgConnection,
+	chain: &str,
+	height: u64,
+	entry: &ConsensusEntry,
+) -> Result<(), Error> {
+	store_attestations(conn, chain, height, entry)?;
+	store_slashings(conn, chain, height, entry)?;
+	store_voluntary_exits(conn, chain, height, entry)?;
+	store_sync_participations(conn, chain, height, entry)?;
+	store_consensus_deposits(conn, chain, height, entry)?;
+
+	Ok(())
+}
+
+// Delete the attestations, slashings, exits, sync committee participations and deposits included
+// in the block at `height`
+//
+// Attestation duties are kept, they don't depend on the block
+fn delete_block_content(conn: &PgConnection, chain: &str, height: u64) -> Result<(), Error> {
+	NewAttestationInclusion::delete_at(conn, chain, height)?;
+	NewSlashing::delete_at(conn, chain, height)?;
+	NewVoluntaryExit::delete_at(conn, chain, height)?;
+	NewSyncParticipation::delete_at(conn, chain, height)?;
+	NewConsensusDeposit::delete_at(conn, chain, height)?;
+
+	Ok(())
+}
+
+// Store the attestation duties of the slot and the attestations included in its block
+fn store_attestations(
+	conn: &PgConnection,
+	chain: &str,
+	height: u64,
+	entry: &ConsensusEntry,
+) -> Result<(), Error> {
+	let new_duties: NewAttestationDuties = entry
+		.duties
+		.iter()
+		.flat_map(|c| {
+			c.validators.iter().map(move |v| {
+				NewAttestationDuty::new(
+					chain,
+					*v,
+					epoch_of_slot(c.slot.as_u64()),
+					c.slot.as_u64(),
+					c.index,
+				)
+			})
+		})
+		.collect();
+	new_duties.batch_insert(conn)?;
+
+	let block = match &entry.block {
+		Some(b) => b,
+		None => return Ok(()),
+	};
+
+	// A set aggregation bit means the validator at the same position in the committee voted
+	let mut new_inclusions = vec![];
+	for attestation in block.message().body().attestations().iter() {
+		let data = &attestation.data;
+		let committee = entry
+			.attested_committees
+			.iter()
+			.find(|c| c.slot == data.slot && c.index == data.index)
+			.ok_or_else(|| SyncError::NoCommittees(data.slot.as_u64()))?;
+
+		for (validator_index, voted) in
+			committee.validators.iter().zip(attestation.aggregation_bits.iter())
+		{
+			if !voted {
+				continue
+			}
+			new_inclusions.push(NewAttestationInclusion::new(
+				chain,
+				*validator_index,
+				epoch_of_slot(data.slot.as_u64()),
+				height,
+				data.beacon_block_root,
+				(data.source.epoch.as_u64(), data.source.root),
+				(data.target.epoch.as_u64(), data.target.root),
+			));
+		}
+	}
+	NewAttestationInclusions::from_iter(new_inclusions).batch_insert(conn)?;
+
+	Ok(())
+}
+
+// Store the offenders of the proposer and attester slashings included in the block
+fn store_slashings(
+	conn: &PgConnection,
+	chain: &str,
+	height: u64,
+	entry: &ConsensusEntry,
+) -> Result<(), Error> {
+	let block = match &entry.block {
+		Some(b) => b,
+		None => return Ok(()),
+	};
+	let body = block.message().body();
+	let whistleblower_index = block.message().proposer_index();
+
+	let mut new_slashings = vec![];
+	for slashing in body.proposer_slashings().iter() {
+		new_slashings.push(NewSlashing::new(
+			chain,
+			slashing.signed_header_1.message.proposer_index,
+			height,
+			SlashingType::Proposer,
+			whistleblower_index,
+		));
+	}
+	// Validators are slashed for signing both attestations
+	for slashing in body.attester_slashings().iter() {
+		let attesting_indices_2 = &slashing.attestation_2.attesting_indices;
+		for validator_index in slashing.attestation_1.attesting_indices.iter() {
+			if !attesting_indices_2.contains(validator_index) {
+				continue
+			}
+			new_slashings.push(NewSlashing::new(
+				chain,
+				*validator_index,
+				height,
+				SlashingType::Attester,
+				whistleblower_index,
+			));
+		}
+	}
+
+	if !new_slashings.is_empty() {
+		info!(
+			"{} validators slashed at slot {height}",
+			new_slashings.len()
+		);
+		NewSlashings::from_iter(new_slashings).batch_insert(conn)?;
+	}
+
+	Ok(())
+}
+
+// Store the voluntary exits included in the block
+fn store_voluntary_exits(
+	conn: &PgConnection,
+	chain: &str,
+	height: u64,
+	entry: &ConsensusEntry,
+) -> Result<(), Error> {
+	let block = match &entry.block {
+		Some(b) => b,
+		None => return Ok(()),
+	};
+
+	let new_exits: NewVoluntaryExits = block
+		.message()
+		.body()
+		.voluntary_exits()
+		.iter()
+		.map(|e| {
+			NewVoluntaryExit::new(
+				chain,
+				e.message.validator_index,
+				e.message.epoch.as_u64(),
+				height,
+			)
+		})
+		.collect();
+	let inserted = new_exits.batch_insert(conn)?;
+	if inserted > 0 {
+		info!("{inserted} validators requested an exit at slot {height}");
+	}
+
+	Ok(())
+}
+
+// Store whether each sync committee member signed the sync aggregate of the block
+fn store_sync_participations(
+	conn: &PgConnection,
+	chain: &str,
+	height: u64,
+	entry: &ConsensusEntry,
+) -> Result<(), Error> {
+	let (block, committee) = match (&entry.block, &entry.sync_committee) {
+		(Some(b), Some(c)) => (b, c),
+		_ => return Ok(()),
+	};
+	let sync_aggregate = match block.message().body().sync_aggregate() {
+		Ok(a) => a,
+		Err(_) => return Ok(()),
+	};
+
+	if !committee.stored {
+		let new_members: NewSyncCommitteeMembers = committee
+			.members
+			.iter()
+			.enumerate()
+			.map(|(position, v)| {
+				NewSyncCommitteeMember::new(chain, committee.period, position as u32, *v)
+			})
+			.collect();
+		new_members.batch_insert(conn)?;
+	}
+
+	// The bit at each position tells whether the member at this position signed
+	let new_participations: NewSyncParticipations = committee
+		.members
+		.iter()
+		.zip(sync_aggregate.sync_committee_bits.iter())
+		.enumerate()
+		.map(|(position, (v, participated))| {
+			NewSyncParticipation::new(chain, height, position as u32, *v, participated)
+		})
+		.collect();
+	new_participations.batch_insert(conn)?;
+
+	Ok(())
+}
+
+// Store the deposits processed by the block
+fn store_consensus_deposits(
+	conn: &PgConnection,
+	chain: &str,
+	height: u64,
+	entry: &ConsensusEntry,
+) -> Result<(), Error> {
+	let deposits = match &entry.block {
+		Some(b) if !b.message().body().deposits().is_empty() => b.message().body().deposits(),
+		_ => return Ok(()),
+	};
+
+	let first_deposit_index = find_first_deposit_index(conn, chain, height, deposits)?
+		.ok_or(SyncError::UnknownDeposits(height))?;
+
+	let new_deposits: NewConsensusDeposits = deposits
+		.iter()
+		.enumerate()
+		.map(|(i, d)| {
+			NewConsensusDeposit::new(
+				chain,
+				first_deposit_index + i as u64,
+				height,
+				d.data.pubkey.as_hex_string(),
+				d.data.withdrawal_credentials,
+				d.data.amount,
+				d.data.signature.serialize().to_vec(),
+			)
+		})
+		.collect();
+	new_deposits.batch_insert(conn)?;
+
+	Ok(())
+}
+
+// Return the index of the first deposit processed by the block at `height`
+//
+// The deposits of a block are consecutive in the deposit contract, so they are matched against
+// the ones synced by the execution syncer. Identical deposits can be made several times, the
+// lowest index not processed by another block is picked.
+// Return None if the deposits were not synced by the execution syncer yet.
+fn find_first_deposit_index(
+	conn: &PgConnection,
+	chain: &str,
+	height: u64,
+	deposits: &[Deposit],
+) -> Result<Option<u64>, Error> {
+	let first = match deposits.first() {
+		Some(d) => d,
+		None => return Ok(None),
+	};
+
+	let candidates =
+		ExecutionDeposit::list_for_pubkey(conn, chain, &first.data.pubkey.as_hex_string())?;
+	for candidate in candidates.iter().filter(|c| is_same_deposit(c, first)) {
+		let index = candidate.deposit_index();
+		let processed = ConsensusDeposit::get(conn, chain, index).optional()?;
+		if matches!(processed, Some(p) if p.inclusion_slot() != height) {
+			continue
+		}
+
+		let last_index = index + deposits.len() as u64 - 1;
+		let stored = ExecutionDeposit::list_range(conn, chain, index, last_index)?;
+		if stored.len() == deposits.len()
+			&& stored.iter().zip(deposits).all(|(s, d)| is_same_deposit(s, d))
+		{
+			return Ok(Some(index))
+		}
+	}
+
+	Ok(None)
+}
+
+// Return true if the deposit emitted by the deposit contract carries the data of `deposit`
+fn is_same_deposit(stored: &ExecutionDeposit, deposit: &Deposit) -> bool {
+	stored.pubkey() == deposit.data.pubkey.as_hex_string()
+		&& stored.withdrawal_credentials() == deposit.data.withdrawal_credentials
+		&& stored.amount() == deposit.data.amount
+		&& stored.signature() == deposit.data.signature.serialize().as_slice()
+}
+
+// Build the slot row of `block`
+fn new_slot_from_block(
+	chain: &str,
+	height: u64,
+	block: &SignedBeaconBlock<MainnetEthSpec>,
+	finalized: Option<bool>,
+) -> NewSlot {
+	// Retrieve block hash and block number from the block
+	let block_hash = block
+		.message()
+		.body()
+		.execution_payload()
+		.ok()
+		.map(|p| p.block_hash.into_root());
+	let block_number = block.message().body().execution_payload().ok().map(|p| p.block_number);
+
+	// Postgres doesn't accept NUL characters in text, graffiti are right padded with them
+	let graffiti = block.message().body().graffiti().as_utf8_lossy().replace('\0', "");
+	let randao_reveal = block.message().body().randao_reveal().serialize().to_vec();
+
+	NewSlot::new(
+		chain,
+		height,
+		block.canonical_root(),
+		block_hash,
+		block_number,
+		block.message().proposer_index(),
+		graffiti,
+		randao_reveal,
+		finalized,
+	)
+}
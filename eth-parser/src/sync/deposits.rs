@@ -0,0 +1,79 @@
+use std::collections::HashMap;
+
+use diesel::Connection;
+use kiln_postgres::{
+	ConsensusDeposit, ExecutionDeposit, NewDepositReconciliation, NewDepositReconciliations,
+	PgConnectionPool, ReconciliationStatus, Validator,
+};
+use log::{info, warn};
+
+use crate::{error::Error, network::Network};
+
+/// Match every DepositEvent emitted by the deposit contract with the beacon chain deposit it
+/// produced
+///
+/// Both layers share the deposit index, so deposits made through internal calls are matched as
+/// well. A deposit included in a beacon block is processed if a validator exists for its public
+/// key. Otherwise its signature was invalid and the beacon chain ignored it.
+/// A deposit with an invalid signature followed by a valid one for the same public key is
+/// reported as processed.
+///
+/// Return the number of deposits that are not processed
+pub fn reconcile_deposits(conn_pool: PgConnectionPool, network: &Network) -> Result<usize, Error> {
+	let chain = network.name();
+	let conn = conn_pool.get().unwrap();
+
+	let execution_deposits = ExecutionDeposit::list(&conn, chain)?;
+	let validator_pubkeys = Validator::list_pubkeys(&conn, chain)?;
+	let consensus_deposits: HashMap<u64, ConsensusDeposit> = ConsensusDeposit::list(&conn, chain)?
+		.into_iter()
+		.map(|d| (d.deposit_index(), d))
+		.collect();
+	info!(
+		"Reconciling {} execution deposits with {} consensus deposits",
+		execution_deposits.len(),
+		consensus_deposits.len()
+	);
+
+	let mut not_processed = 0;
+	let mut new_reconciliations = vec![];
+	for deposit in execution_deposits {
+		let status = match consensus_deposits.get(&deposit.deposit_index()) {
+			Some(d) if d.pubkey() != deposit.pubkey() => {
+				warn!(
+					"Deposit {} differs between the execution and the consensus layers",
+					deposit.deposit_index()
+				);
+				continue
+			},
+			Some(_) if validator_pubkeys.contains(deposit.pubkey()) =>
+				ReconciliationStatus::Processed,
+			Some(_) => ReconciliationStatus::InvalidSignature,
+			None => ReconciliationStatus::Unprocessed,
+		};
+		if status != ReconciliationStatus::Processed {
+			warn!(
+				"Deposit {} in transaction {:?} is {}",
+				deposit.deposit_index(),
+				deposit.transaction_hash(),
+				status.as_str()
+			);
+			not_processed += 1;
+		}
+
+		new_reconciliations.push(NewDepositReconciliation::new(
+			chain,
+			deposit.deposit_index(),
+			deposit.transaction_hash(),
+			status,
+		));
+	}
+
+	conn.transaction::<_, Error, _>(|| {
+		NewDepositReconciliations::from_iter(new_reconciliations).batch_upsert(&conn)?;
+
+		Ok(())
+	})?;
+
+	Ok(not_processed)
+}
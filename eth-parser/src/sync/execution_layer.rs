@@ -1,44 +1,187 @@
-use std::fmt::Display;
+use std::{
+	collections::{HashMap, HashSet},
+	fmt::Display,
+	future::Future,
+	ops::RangeInclusive,
+	sync::Arc,
+};
 
 use async_trait::async_trait;
-use ethereum_abi::Abi;
+use diesel::{Connection, OptionalExtension, PgConnection};
 use futures::future::try_join_all;
 use kiln_postgres::{
-	ExecBlock, NewExecBlock, NewTransaction, NewTransactions, NewValidator, PgConnectionPool,
+	AccessListItem, ExecBlock, NewContract, NewContracts, NewDecodedCall, NewDecodedCalls,
+	NewDecodedEvent, NewDecodedEvents, NewDeposits, NewExecBlock, NewExecutionDeposit,
+	NewExecutionDeposits, NewInternalCall, NewInternalCalls, NewLog, NewLogs, NewTokenTransfer,
+	NewTokenTransfers, NewTransaction, NewTransactions, PgConnectionPool, TokenStandard,
 };
-use log::{error, info};
+use log::{info, warn};
 use web3::{
+	ethabi::{Contract, Event, RawLog, Token},
+	signing::keccak256,
 	transports::Http,
-	types::{Transaction, H160, H256},
+	types::{Block, Log, Transaction, TransactionReceipt, H160, H256, U256},
 	Web3,
 };
 
 use super::{syncer::DbSyncer, SyncError};
 
-use crate::{client_execution, Error};
-
-// The deposit contract address for the kiln network
-//
-// # value
-// 0x4242424242424242424242424242424242424242
-//
-// # explorer
-// https://explorer.kiln.themerge.dev/address/0x4242424242424242424242424242424242424242/transactions
-const DEPOSIT_CONTRACT_ADDRESS: [u8; 20] = [
-	0x42, 0x42, 0x42, 0x42, 0x42, 0x42, 0x42, 0x42, 0x42, 0x42, 0x42, 0x42, 0x42, 0x42, 0x42, 0x42,
-	0x42, 0x42, 0x42, 0x42,
-];
+use crate::{
+	abi_registry::AbiRegistry,
+	client_execution::{self, CallFrame},
+	network::Network,
+	Error,
+};
 
 lazy_static! {
-	static ref DEPOSIT_CONTRACT_ABI: Abi = serde_json::from_str(r#"[{"inputs":[],"stateMutability":"nonpayable","type":"constructor"},{"anonymous":false,"inputs":[{"indexed":false,"internalType":"bytes","name":"pubkey","type":"bytes"},{"indexed":false,"internalType":"bytes","name":"withdrawal_credentials","type":"bytes"},{"indexed":false,"internalType":"bytes","name":"amount","type":"bytes"},{"indexed":false,"internalType":"bytes","name":"signature","type":"bytes"},{"indexed":false,"internalType":"bytes","name":"index","type":"bytes"}],"name":"DepositEvent","type":"event"},{"inputs":[{"internalType":"bytes","name":"pubkey","type":"bytes"},{"internalType":"bytes","name":"withdrawal_credentials","type":"bytes"},{"internalType":"bytes","name":"signature","type":"bytes"},{"internalType":"bytes32","name":"deposit_data_root","type":"bytes32"}],"name":"deposit","outputs":[],"stateMutability":"payable","type":"function"},{"inputs":[],"name":"get_deposit_count","outputs":[{"internalType":"bytes","name":"","type":"bytes"}],"stateMutability":"view","type":"function"},{"inputs":[],"name":"get_deposit_root","outputs":[{"internalType":"bytes32","name":"","type":"bytes32"}],"stateMutability":"view","type":"function"},{"inputs":[{"internalType":"bytes4","name":"interfaceId","type":"bytes4"}],"name":"supportsInterface","outputs":[{"internalType":"bool","name":"","type":"bool"}],"stateMutability":"pure","type":"function"}]"#).unwrap();
+	static ref DEPOSIT_CONTRACT_ABI: Contract = Contract::load(r#"[{"inputs":[],"stateMutability":"nonpayable","type":"constructor"},{"anonymous":false,"inputs":[{"indexed":false,"internalType":"bytes","name":"pubkey","type":"bytes"},{"indexed":false,"internalType":"bytes","name":"withdrawal_credentials","type":"bytes"},{"indexed":false,"internalType":"bytes","name":"amount","type":"bytes"},{"indexed":false,"internalType":"bytes","name":"signature","type":"bytes"},{"indexed":false,"internalType":"bytes","name":"index","type":"bytes"}],"name":"DepositEvent","type":"event"},{"inputs":[{"internalType":"bytes","name":"pubkey","type":"bytes"},{"internalType":"bytes","name":"withdrawal_credentials","type":"bytes"},{"internalType":"bytes","name":"signature","type":"bytes"},{"internalType":"bytes32","name":"deposit_data_root","type":"bytes32"}],"name":"deposit","outputs":[],"stateMutability":"payable","type":"function"},{"inputs":[],"name":"get_deposit_count","outputs":[{"internalType":"bytes","name":"","type":"bytes"}],"stateMutability":"view","type":"function"},{"inputs":[],"name":"get_deposit_root","outputs":[{"internalType":"bytes32","name":"","type":"bytes32"}],"stateMutability":"view","type":"function"},{"inputs":[{"internalType":"bytes4","name":"interfaceId","type":"bytes4"}],"name":"supportsInterface","outputs":[{"internalType":"bool","name":"","type":"bool"}],"stateMutability":"pure","type":"function"}]"#.as_bytes()).unwrap();
+	static ref DEPOSIT_EVENT: Event = DEPOSIT_CONTRACT_ABI.event("DepositEvent").unwrap().clone();
+	// ERC-20 and ERC-721 Transfer events share their signature, only the indexed token id differs
+	static ref ERC20_TRANSFER_EVENT: Event = Contract::load(r#"[{"anonymous":false,"inputs":[{"indexed":true,"internalType":"address","name":"from","type":"address"},{"indexed":true,"internalType":"address","name":"to","type":"address"},{"indexed":false,"internalType":"uint256","name":"value","type":"uint256"}],"name":"Transfer","type":"event"}]"#.as_bytes()).unwrap().event("Transfer").unwrap().clone();
+	static ref ERC721_TRANSFER_EVENT: Event = Contract::load(r#"[{"anonymous":false,"inputs":[{"indexed":true,"internalType":"address","name":"from","type":"address"},{"indexed":true,"internalType":"address","name":"to","type":"address"},{"indexed":true,"internalType":"uint256","name":"tokenId","type":"uint256"}],"name":"Transfer","type":"event"}]"#.as_bytes()).unwrap().event("Transfer").unwrap().clone();
+	static ref ERC1155_ABI: Contract = Contract::load(r#"[{"anonymous":false,"inputs":[{"indexed":true,"internalType":"address","name":"operator","type":"address"},{"indexed":true,"internalType":"address","name":"from","type":"address"},{"indexed":true,"internalType":"address","name":"to","type":"address"},{"indexed":false,"internalType":"uint256","name":"id","type":"uint256"},{"indexed":false,"internalType":"uint256","name":"value","type":"uint256"}],"name":"TransferSingle","type":"event"},{"anonymous":false,"inputs":[{"indexed":true,"internalType":"address","name":"operator","type":"address"},{"indexed":true,"internalType":"address","name":"from","type":"address"},{"indexed":true,"internalType":"address","name":"to","type":"address"},{"indexed":false,"internalType":"uint256[]","name":"ids","type":"uint256[]"},{"indexed":false,"internalType":"uint256[]","name":"values","type":"uint256[]"}],"name":"TransferBatch","type":"event"}]"#.as_bytes()).unwrap();
+	static ref ERC1155_TRANSFER_SINGLE_EVENT: Event = ERC1155_ABI.event("TransferSingle").unwrap().clone();
+	static ref ERC1155_TRANSFER_BATCH_EVENT: Event = ERC1155_ABI.event("TransferBatch").unwrap().clone();
 }
 
-pub(crate) struct ExecutionSyncer(PgConnectionPool, Web3<Http>);
+pub(crate) struct ExecutionSyncer {
+	conn_pool: PgConnectionPool,
+	client: Web3<Http>,
+	network: Network,
+	max_in_flight: usize,
+	/// Trace the internal calls of the transactions
+	trace_calls: bool,
+	/// ABIs used to decode the transactions inputs and the logs
+	abi_registry: Arc<AbiRegistry>,
+}
 
 impl ExecutionSyncer {
-	pub fn new(conn: PgConnectionPool, client: Web3<Http>) -> ExecutionSyncer {
-		ExecutionSyncer(conn, client)
+	pub fn new(
+		conn: PgConnectionPool,
+		client: Web3<Http>,
+		network: Network,
+		max_in_flight: usize,
+		trace_calls: bool,
+		abi_registry: Arc<AbiRegistry>,
+	) -> ExecutionSyncer {
+		ExecutionSyncer {
+			conn_pool: conn,
+			client,
+			network,
+			max_in_flight,
+			trace_calls,
+			abi_registry,
+		}
+	}
+
+	/// Make sure the block at `height` extends the chain stored in database
+	///
+	/// If the block stored at `height - 1` is not the parent of the new block, the node switched
+	/// to another branch. Walk back to the common ancestor and fetch the canonical blocks up to
+	/// `height - 1`.
+	///
+	/// Return the height from which the stored blocks are orphaned, along the canonical entries
+	/// that replace them, None if there is no reorg. Nothing is written, the caller deletes the
+	/// orphaned blocks and stores the canonical ones along the new block.
+	///
+	/// Fail with `SyncError::StaleEntry` if the stored chain is canonical but the new block is
+	/// not, so that the caller fetches it again.
+	async fn handle_reorg(
+		&self,
+		height: u64,
+		parent_hash: H256,
+	) -> Result<Option<(u64, Vec<ExecutionEntry>)>, Error> {
+		if height == 0 {
+			return Ok(None)
+		}
+
+		let opt_stored_parent =
+			ExecBlock::get(&self.conn_pool.get().unwrap(), self.chain(), height - 1).optional()?;
+		match opt_stored_parent {
+			Some(parent) if parent.hash() != parent_hash => {},
+			// Parent is canonical or was never synced
+			_ => return Ok(None),
+		}
+
+		// Entries are prefetched, so this one may come from the branch the node left after the
+		// stored chain was already rolled back. The stored chain is then canonical and the entry
+		// must be fetched again
+		let fork_height = self.find_fork_height(height - 1).await?;
+		if fork_height == height {
+			warn!("{self}: Entry at height {height} was fetched from an orphaned branch");
+			return Err(SyncError::StaleEntry(height).into())
+		}
+		warn!("{self}: Reorg detected at height {height}, rolling back to height {fork_height}");
+
+		let mut canonical_entries = vec![];
+		for h in fork_height..height {
+			canonical_entries.push(self.fetch_entry(h).await?);
+		}
+
+		// The node may have switched branch again while the canonical blocks were fetched
+		let opt_last_hash = canonical_entries.last().and_then(|e| e.block.hash);
+		if opt_last_hash != Some(parent_hash) {
+			warn!("{self}: Entry at height {height} does not extend the re-fetched blocks");
+			return Err(SyncError::StaleEntry(height).into())
+		}
+
+		Ok(Some((fork_height, canonical_entries)))
+	}
+
+	/// Return the height of the first stored block that is not part of the canonical chain
+	///
+	/// Walk back from `height` until the hash stored in database matches the one served by the
+	/// node, or until there is nothing stored anymore. Heights that were never synced are skipped.
+	async fn find_fork_height(&self, height: u64) -> Result<u64, Error> {
+		search_fork_height(
+			height,
+			|h| {
+				let opt_block =
+					ExecBlock::get_highest_up_to(&self.conn_pool.get().unwrap(), self.chain(), h)
+						.optional()?;
+				Ok(opt_block.map(|b| (b.number(), b.hash())))
+			},
+			|h| {
+				let client = self.node_client();
+				async move {
+					let opt_block = client_execution::get_block(client, h).await?;
+					Ok(opt_block.and_then(|b| b.hash))
+				}
+			},
+		)
+		.await
+	}
+}
+
+// Return the height of the first block of the stored chain that is not part of the canonical one,
+// walking back from `height`
+//
+// `stored` returns the number and hash of the highest stored block up to a height, so that the
+// heights never synced are skipped. `canonical` returns the hash of the block served by the node
+// at a height.
+async fn search_fork_height<S, C, F>(height: u64, stored: S, canonical: C) -> Result<u64, Error>
+where
+	S: Fn(u64) -> Result<Option<(u64, H256)>, Error>,
+	C: Fn(u64) -> F,
+	F: Future<Output = Result<Option<H256>, Error>>,
+{
+	let mut fork_height = height + 1;
+	let mut opt_height = Some(height);
+	while let Some(h) = opt_height {
+		let (number, stored_hash) = match stored(h)? {
+			Some(b) => b,
+			None => break,
+		};
+		let canonical_hash = canonical(number).await?.ok_or(SyncError::NothingAtHeight(number))?;
+
+		if canonical_hash == stored_hash {
+			break
+		}
+		fork_height = number;
+		opt_height = number.checked_sub(1);
 	}
+
+	Ok(fork_height)
 }
 
 impl Display for ExecutionSyncer {
@@ -49,123 +192,822 @@ impl Display for ExecutionSyncer {
 
 #[async_trait]
 impl DbSyncer for ExecutionSyncer {
+	type Entry = ExecutionEntry;
 	type NodeClient = Web3<Http>;
 
+	fn conn_pool(&self) -> &PgConnectionPool {
+		&self.conn_pool
+	}
+
+	fn chain(&self) -> &str {
+		self.network.name()
+	}
+
 	fn node_client(&self) -> Self::NodeClient {
-		self.1.clone()
+		self.client.clone()
+	}
+
+	fn max_in_flight(&self) -> usize {
+		self.max_in_flight
 	}
 
 	fn get_db_height(&self) -> Result<u64, Error> {
-		let block = ExecBlock::get_highest(&self.0.get().unwrap())?;
+		let block = ExecBlock::get_highest(&self.conn_pool.get().unwrap(), self.chain())?;
 
 		Ok(block.number())
 	}
 
-	async fn create_new_entry(&self, height: u64) -> Result<(), Error> {
+	fn list_missing_ranges(&self) -> Result<Vec<RangeInclusive<u64>>, Error> {
+		let ranges = ExecBlock::list_missing_ranges(&self.conn_pool.get().unwrap(), self.chain())?;
+
+		Ok(ranges)
+	}
+
+	async fn fetch_entry(&self, height: u64) -> Result<Self::Entry, Error> {
 		// Get block from client
 		let block = client_execution::get_block(self.node_client(), height)
 			.await?
 			.ok_or(SyncError::NothingAtHeight(height))?;
 
-		// Handle and insert block
+		// Get the receipts of every transaction, in block order
+		let hashes: Vec<H256> = block.transactions.iter().map(|t| t.hash).collect();
+		let receipts: Vec<TransactionReceipt> =
+			client_execution::get_transaction_receipts(self.node_client(), &hashes)
+				.await?
+				.into_iter()
+				.zip(hashes)
+				.map(|(r, h)| r.ok_or(SyncError::NoReceipt(h)))
+				.collect::<Result<_, _>>()?;
+
+		// Trace the internal calls, in block order
+		let traces = if self.trace_calls {
+			client_execution::trace_block(self.node_client(), height).await?
+		} else {
+			vec![]
+		};
+
+		// Get the bytecode of the contracts created by successful transactions, a reverted
+		// deployment still reports the address it would have used
+		let mut created_contracts: Vec<H160> = receipts
+			.iter()
+			.filter(|r| r.status.map_or(true, |s| !s.is_zero()))
+			.filter_map(|r| r.contract_address)
+			.collect();
+		for trace in traces.iter() {
+			created_contracts.extend(list_internal_calls(trace).into_iter().filter_map(
+				|(_, call, reverted)| {
+					if is_creation(call) && !reverted {
+						call.to
+					} else {
+						None
+					}
+				},
+			));
+		}
+		let bytecodes = try_join_all(
+			created_contracts
+				.iter()
+				.map(|a| client_execution::get_code(self.node_client(), *a, height)),
+		)
+		.await?;
+
+		Ok(ExecutionEntry {
+			block,
+			receipts,
+			traces,
+			bytecodes: created_contracts.into_iter().zip(bytecodes).collect(),
+		})
+	}
+
+	async fn store_entry(&self, height: u64, entry: Self::Entry) -> Result<(), Error> {
+		// Roll back the stored chain if the node is now following another branch
+		let opt_reorg = self.handle_reorg(height, entry.block.parent_hash).await?;
+
+		let mut rows = vec![];
+		let mut opt_fork_height = None;
+		if let Some((fork_height, canonical_entries)) = opt_reorg {
+			for (h, canonical_entry) in (fork_height..height).zip(canonical_entries) {
+				rows.push(self.block_rows(h, canonical_entry)?);
+			}
+			opt_fork_height = Some(fork_height);
+		}
+		rows.push(self.block_rows(height, entry)?);
+
+		// Delete the orphaned blocks and write the canonical ones in a single db transaction, so
+		// that a failure never leaves a hole in the stored chain
+		let conn = self.conn_pool.get().unwrap();
+		conn.transaction::<_, Error, _>(|| {
+			if let Some(fork_height) = opt_fork_height {
+				let deleted = ExecBlock::delete_from(&conn, self.chain(), fork_height)?;
+				info!("{self}: Deleted {deleted} orphaned blocks");
+			}
+			for block_rows in rows.iter() {
+				self.write_block_rows(&conn, block_rows)?;
+			}
+
+			Ok(())
+		})?;
+		if let Some(fork_height) = opt_fork_height {
+			info!("{self}: Re-synced entries from height {fork_height} to {height}");
+		}
+
+		Ok(())
+	}
+}
+
+/// The rows written in database for a block
+struct BlockRows {
+	block: NewExecBlock,
+	transactions: NewTransactions,
+	logs: NewLogs,
+	decoded_calls: NewDecodedCalls,
+	decoded_events: NewDecodedEvents,
+	token_transfers: NewTokenTransfers,
+	internal_calls: NewInternalCalls,
+	contracts: NewContracts,
+	deposits: NewExecutionDeposits,
+	/// Transactions that emitted a DepositEvent
+	deposit_transactions: HashSet<H256>,
+}
+
+impl ExecutionSyncer {
+	// Build the rows of the block at `height` from its entry
+	fn block_rows(&self, height: u64, entry: ExecutionEntry) -> Result<BlockRows, Error> {
+		let ExecutionEntry {
+			block,
+			receipts,
+			traces,
+			bytecodes,
+		} = entry;
+
+		// Handle block
 		let new_block = NewExecBlock::new(
+			self.chain(),
 			block.hash.ok_or(SyncError::PendingBlock(height))?,
 			block.number.ok_or(SyncError::PendingBlock(height))?.as_u64(),
 			block.parent_hash,
 			block.state_root,
 			block.transactions_root,
 			block.receipts_root,
+		)
+		.with_payload(
+			block.timestamp.as_u64(),
+			block.author,
+			block.gas_limit.as_u64(),
+			block.gas_used.as_u64(),
+			block.base_fee_per_gas,
+			block.extra_data.0,
+			block.logs_bloom.map(|b| b.as_bytes().to_vec()),
+			block.mix_hash,
 		);
-		new_block.insert(&self.0.get().unwrap())?;
-
-		// async calls to execute after all new transactions are stored in db
-		let mut futures = vec![];
-
-		// Handle and insert transactions
-		let mut new_transactions = Vec::with_capacity(block.transactions.len());
-		block.transactions.into_iter().for_each(|t: Transaction| {
-			match t.to {
-				Some(to) if to == H160::from(DEPOSIT_CONTRACT_ADDRESS) => futures.push(
-					link_validator_to_depositor(self.node_client(), self.0.clone(), t.clone()),
-				),
-				_ => {},
-			};
-
-			new_transactions.push(NewTransaction::new(
-				t.hash,
-				t.block_hash.unwrap(),
-				t.transaction_index.unwrap().as_u64(),
-				t.from,
-				t.to,
-				t.input.0,
-				t.value,
-			));
-		});
 
-		NewTransactions::new(new_transactions).batch_insert(&self.0.get().unwrap())?;
+		// Decode the inputs of the transactions sent to a contract known by the registry
+		let new_decoded_calls: NewDecodedCalls = block
+			.transactions
+			.iter()
+			.filter_map(|t| {
+				let to = t.to?;
+				let decoded = self.abi_registry.decode_input(to, &t.input.0)?;
+				Some(NewDecodedCall::new(
+					self.chain(),
+					t.hash,
+					to,
+					decoded.name,
+					decoded.signature,
+					decoded.params,
+				))
+			})
+			.collect();
+
+		// Handle transactions
+		let new_transactions: NewTransactions = block
+			.transactions
+			.into_iter()
+			.zip(receipts.iter())
+			.map(|(t, r): (Transaction, &TransactionReceipt)| {
+				NewTransaction::new(
+					self.chain(),
+					t.hash,
+					t.block_hash.unwrap(),
+					t.transaction_index.unwrap().as_u64(),
+					t.from,
+					t.to,
+					t.input.0,
+					t.value,
+				)
+				.with_fees(
+					t.nonce.as_u64(),
+					t.gas.as_u64(),
+					t.gas_price,
+					t.max_fee_per_gas,
+					t.max_priority_fee_per_gas,
+					t.transaction_type.map(|t| t.as_u32()),
+					t.access_list.map(|a| {
+						a.into_iter()
+							.map(|i| AccessListItem {
+								address: i.address,
+								storage_keys: i.storage_keys,
+							})
+							.collect()
+					}),
+				)
+				.with_receipt(
+					r.status.map(|s| !s.is_zero()),
+					r.gas_used.map(|g| g.as_u64()),
+					r.cumulative_gas_used.as_u64(),
+					r.effective_gas_price,
+					r.contract_address,
+				)
+			})
+			.collect();
+
+		// Handle internal calls, traces are in the same order as the receipts. Deposits made by a
+		// contract are already handled through the DepositEvent logs
+		let mut new_internal_calls = vec![];
+		let mut internal_creations = vec![];
+		for (receipt, trace) in receipts.iter().zip(traces.iter()) {
+			for (position, (depth, call, reverted)) in
+				list_internal_calls(trace).into_iter().enumerate()
+			{
+				new_internal_calls.push(NewInternalCall::new(
+					self.chain(),
+					receipt.transaction_hash,
+					position as u64,
+					depth,
+					call.call_type.clone(),
+					call.from,
+					call.to,
+					call.value.unwrap_or_default(),
+					call.error.clone(),
+				));
+				if let (true, false, Some(address)) = (is_creation(call), reverted, call.to) {
+					internal_creations.push((address, call.from, receipt.transaction_hash));
+				}
+			}
+		}
+		let new_internal_calls = NewInternalCalls::from_iter(new_internal_calls);
+
+		// Handle contracts, the creator of a top level deployment is the sender of the transaction
+		// and the creator of an internal one is the calling contract
+		let new_contracts: NewContracts = receipts
+			.iter()
+			.filter_map(|r| Some((r.contract_address?, r.from, r.transaction_hash)))
+			.chain(internal_creations)
+			.filter_map(|(address, creator, transaction)| {
+				let bytecode = bytecodes.get(&address)?;
+				Some(NewContract::new(
+					self.chain(),
+					address,
+					creator,
+					transaction,
+					height,
+					H256::from(keccak256(bytecode)),
+					bytecode.clone(),
+				))
+			})
+			.collect();
+
+		// Handle logs, reverted transactions do not emit any
+		let logs: Vec<&Log> = receipts.iter().flat_map(|r| r.logs.iter()).collect();
+		let new_logs: NewLogs = logs
+			.iter()
+			.map(|l| {
+				NewLog::new(
+					self.chain(),
+					// Safe to unwrap because the block is not pending
+					l.transaction_hash.unwrap(),
+					l.log_index.unwrap().as_u64(),
+					l.address,
+					&l.topics,
+					l.data.0.clone(),
+				)
+			})
+			.collect();
+
+		// Decode the logs of the events known by the registry
+		let new_decoded_events: NewDecodedEvents = logs
+			.iter()
+			.filter_map(|l| {
+				let decoded = self.abi_registry.decode_log(l)?;
+				Some(NewDecodedEvent::new(
+					self.chain(),
+					// Safe to unwrap because the block is not pending
+					l.transaction_hash.unwrap(),
+					l.log_index.unwrap().as_u64(),
+					l.address,
+					decoded.name,
+					decoded.signature,
+					decoded.params,
+				))
+			})
+			.collect();
+
+		// Handle token transfers, whatever contract emitted them
+		let new_token_transfers: NewTokenTransfers = logs
+			.iter()
+			.flat_map(|l| decode_token_transfers(l))
+			.map(|t| {
+				NewTokenTransfer::new(
+					self.chain(),
+					t.transaction,
+					t.log_index,
+					t.batch_index,
+					t.token,
+					t.standard,
+					t.from,
+					t.to,
+					t.token_id,
+					t.value,
+				)
+			})
+			.collect();
+
+		// Handle deposits, whether the deposit contract was called directly or by another contract
+		let deposit_contract_address = self.network.deposit_contract_address();
+		let deposits: Vec<Deposit> = logs
+			.iter()
+			.filter(|l| l.address == deposit_contract_address)
+			.filter_map(|l| decode_deposit_event(l))
+			.collect();
+		let new_deposits: NewExecutionDeposits = deposits
+			.iter()
+			.map(|d| {
+				NewExecutionDeposit::new(
+					self.chain(),
+					d.index,
+					d.transaction,
+					d.log_index,
+					d.pubkey.clone(),
+					d.withdrawal_credentials,
+					d.amount,
+					d.signature.clone(),
+				)
+			})
+			.collect();
 
-		try_join_all(futures).await?;
+		Ok(BlockRows {
+			block: new_block,
+			transactions: new_transactions,
+			logs: new_logs,
+			decoded_calls: new_decoded_calls,
+			decoded_events: new_decoded_events,
+			token_transfers: new_token_transfers,
+			internal_calls: new_internal_calls,
+			contracts: new_contracts,
+			deposits: new_deposits,
+			deposit_transactions: deposits.iter().map(|d| d.transaction).collect(),
+		})
+	}
+
+	// Write the block, its transactions, their logs, decoded inputs and events, token transfers
+	// and internal calls, the created contracts, the deposits and the validators links
+	//
+	// Must be called within a db transaction, so that a block is never stored without its
+	// transactions
+	fn write_block_rows(&self, conn: &PgConnection, rows: &BlockRows) -> Result<(), Error> {
+		rows.block.insert(conn)?;
+		rows.transactions.batch_insert(conn)?;
+		rows.logs.batch_insert(conn)?;
+		rows.decoded_calls.batch_insert(conn)?;
+		rows.decoded_events.batch_insert(conn)?;
+		rows.token_transfers.batch_insert(conn)?;
+		rows.internal_calls.batch_insert(conn)?;
+		rows.contracts.batch_insert(conn)?;
+		rows.deposits.batch_insert(conn)?;
+		for transaction in rows.deposit_transactions.iter() {
+			link_validators_to_depositor(conn, self.chain(), *transaction)?;
+		}
 
 		Ok(())
 	}
 }
 
-// Create a link in database between a validator and the successful calls to the deposit contract
-// that registered it
-async fn link_validator_to_depositor(
-	client: Web3<Http>,
-	conn_pool: PgConnectionPool,
-	transaction: Transaction,
-) -> Result<(), Error> {
-	let (function, decoded_params) =
-		match DEPOSIT_CONTRACT_ABI.decode_input_from_slice(&transaction.input.0) {
-			Ok(d) => d,
-			Err(_) => return Ok(()),
-		};
+/// A block fetched from the execution node
+pub(crate) struct ExecutionEntry {
+	block: Block<Transaction>,
+	/// Receipts of the transactions, in block order
+	receipts: Vec<TransactionReceipt>,
+	/// Call traces of the transactions, in block order, empty if tracing is disabled
+	traces: Vec<CallFrame>,
+	/// Runtime bytecode of the contracts created in the block, by address
+	bytecodes: HashMap<H160, Vec<u8>>,
+}
 
-	if function.name != "deposit" {
-		return Ok(())
+// Flatten the calls made during a transaction, in execution order
+//
+// Return the depth of each call, 1 for the calls made by the contract the transaction was sent to,
+// along whether the call or one of its callers reverted
+fn list_internal_calls(trace: &CallFrame) -> Vec<(u32, &CallFrame, bool)> {
+	fn walk<'a>(
+		frame: &'a CallFrame,
+		depth: u32,
+		reverted: bool,
+		calls: &mut Vec<(u32, &'a CallFrame, bool)>,
+	) {
+		for call in frame.calls.iter() {
+			let reverted = reverted || call.error.is_some();
+			calls.push((depth, call, reverted));
+			walk(call, depth + 1, reverted, calls);
+		}
 	}
 
-	let status = is_transaction_successful(client, transaction.hash).await?;
-	NewTransaction::set_status(&conn_pool.get().unwrap(), transaction.hash, status)?;
+	let mut calls = vec![];
+	walk(trace, 1, trace.error.is_some(), &mut calls);
+	calls
+}
 
-	let bytes = match &decoded_params.get(0).unwrap().value {
-		ethereum_abi::Value::Bytes(b) => b,
-		_ => return Ok(()),
-	};
-	let mut pubkey = "0x".to_string();
-	pubkey.push_str(&hex::encode(bytes));
-
-	let transaction_hash = transaction.hash;
-	let rows =
-		NewValidator::set_deposit_transaction(&conn_pool.get().unwrap(), pubkey, transaction_hash)?;
-	info!("validator deposit: from {:?}", transaction.from);
-	if rows != 1 {
-		error!(
-			"wrong amount ({rows}) of validators are linked to the transaction {:?}",
-			transaction.hash,
-		);
+// Return true if the call created a contract
+fn is_creation(call: &CallFrame) -> bool {
+	call.call_type == "CREATE" || call.call_type == "CREATE2"
+}
+
+/// A DepositEvent emitted by the deposit contract
+pub(crate) struct Deposit {
+	transaction: H256,
+	log_index: u64,
+	/// Hex encoded, prefixed with 0x
+	pubkey: String,
+	withdrawal_credentials: H256,
+	/// In gwei
+	amount: u64,
+	signature: Vec<u8>,
+	index: u64,
+}
+
+// Decode a DepositEvent log
+//
+// Return None if the log is not a DepositEvent or if it was removed by a reorg
+fn decode_deposit_event(log: &Log) -> Option<Deposit> {
+	if log.removed == Some(true) || log.topics.first() != Some(&DEPOSIT_EVENT.signature()) {
+		return None
 	}
 
-	Ok(())
+	let raw_log = RawLog {
+		topics: log.topics.clone(),
+		data: log.data.0.clone(),
+	};
+	let decoded = match DEPOSIT_EVENT.parse_log(raw_log) {
+		Ok(d) => d,
+		Err(e) => {
+			warn!(
+				"Malformed DepositEvent in transaction {:?}: {e}",
+				log.transaction_hash
+			);
+			return None
+		},
+	};
+
+	let bytes_param = |name: &str| match decoded.params.iter().find(|p| p.name == name) {
+		Some(p) => match &p.value {
+			Token::Bytes(b) => Some(b.clone()),
+			_ => None,
+		},
+		None => None,
+	};
+	// The contract encodes the amount and the index as little endian 64 bits integers
+	let u64_param =
+		|name: &str| bytes_param(name).and_then(|b| b.try_into().ok()).map(u64::from_le_bytes);
+
+	Some(Deposit {
+		transaction: log.transaction_hash?,
+		log_index: log.log_index?.as_u64(),
+		pubkey: format!("0x{}", hex::encode(bytes_param("pubkey")?)),
+		withdrawal_credentials: bytes_param("withdrawal_credentials")
+			.filter(|b| b.len() == 32)
+			.map(|b| H256::from_slice(&b))?,
+		amount: u64_param("amount")?,
+		signature: bytes_param("signature")?,
+		index: u64_param("index")?,
+	})
 }
 
-// Check transaction status on reciept
+/// A token transfer decoded from a Transfer, TransferSingle or TransferBatch event
+pub(crate) struct TokenTransfer {
+	transaction: H256,
+	log_index: u64,
+	/// Position in the ids of a TransferBatch, 0 otherwise
+	batch_index: u32,
+	/// Address of the contract that emitted the event
+	token: H160,
+	standard: TokenStandard,
+	from: H160,
+	to: H160,
+	/// None for an ERC-20 transfer
+	token_id: Option<U256>,
+	/// 1 for an ERC-721 transfer
+	value: U256,
+}
+
+// Decode the token transfers of an ERC-20 or ERC-721 Transfer log, or of an ERC-1155
+// TransferSingle or TransferBatch log
 //
-// # Safety
-// Will panic if called on a transaction that have not been included (pending)
-async fn is_transaction_successful(
-	client: Web3<Http>,
-	transaction_hash: H256,
-) -> Result<bool, Error> {
-	let reciept = client_execution::get_transaction_receipt(client, transaction_hash)
-		.await?
-		// Only safe to unwrap because the transaction have been included
-		.unwrap();
+// Return an empty list if the log is not a transfer or if it was removed by a reorg
+fn decode_token_transfers(log: &Log) -> Vec<TokenTransfer> {
+	let (transaction, log_index) = match (log.removed, log.transaction_hash, log.log_index) {
+		(Some(true), _, _) => return vec![],
+		(_, Some(transaction), Some(log_index)) => (transaction, log_index.as_u64()),
+		_ => return vec![],
+	};
+
+	// A Transfer with 3 topics comes from an ERC-20 token, with 4 topics from an ERC-721 token
+	let (event, standard) = match log.topics.first() {
+		Some(t) if *t == ERC20_TRANSFER_EVENT.signature() && log.topics.len() == 3 =>
+			(&*ERC20_TRANSFER_EVENT, TokenStandard::Erc20),
+		Some(t) if *t == ERC721_TRANSFER_EVENT.signature() && log.topics.len() == 4 =>
+			(&*ERC721_TRANSFER_EVENT, TokenStandard::Erc721),
+		Some(t) if *t == ERC1155_TRANSFER_SINGLE_EVENT.signature() =>
+			(&*ERC1155_TRANSFER_SINGLE_EVENT, TokenStandard::Erc1155),
+		Some(t) if *t == ERC1155_TRANSFER_BATCH_EVENT.signature() =>
+			(&*ERC1155_TRANSFER_BATCH_EVENT, TokenStandard::Erc1155),
+		_ => return vec![],
+	};
+
+	let raw_log = RawLog {
+		topics: log.topics.clone(),
+		data: log.data.0.clone(),
+	};
+	let decoded = match event.parse_log(raw_log) {
+		Ok(d) => d,
+		Err(e) => {
+			warn!(
+				"Malformed {} event in transaction {transaction:?}: {e}",
+				event.name
+			);
+			return vec![]
+		},
+	};
+
+	let param = |name: &str| decoded.params.iter().find(|p| p.name == name).map(|p| &p.value);
+	let address_param = |name: &str| match param(name) {
+		Some(Token::Address(a)) => Some(*a),
+		_ => None,
+	};
+	let uint_param = |name: &str| match param(name) {
+		Some(Token::Uint(u)) => Some(*u),
+		_ => None,
+	};
+	let uints_param = |name: &str| match param(name) {
+		Some(Token::Array(a)) => a
+			.iter()
+			.map(|t| match t {
+				Token::Uint(u) => Some(*u),
+				_ => None,
+			})
+			.collect::<Option<Vec<U256>>>(),
+		_ => None,
+	};
+
+	// List the (token id, value) pairs moved by the event
+	let amounts = match (standard, event.name.as_str()) {
+		(TokenStandard::Erc20, _) => uint_param("value").map(|v| vec![(None, v)]),
+		(TokenStandard::Erc721, _) => uint_param("tokenId").map(|i| vec![(Some(i), U256::one())]),
+		(TokenStandard::Erc1155, "TransferSingle") =>
+			uint_param("id").zip(uint_param("value")).map(|(i, v)| vec![(Some(i), v)]),
+		(TokenStandard::Erc1155, _) => uints_param("ids")
+			.zip(uints_param("values"))
+			.filter(|(ids, values)| ids.len() == values.len())
+			.map(|(ids, values)| ids.into_iter().map(Some).zip(values).collect()),
+	};
+	let (from, to, amounts) = match (address_param("from"), address_param("to"), amounts) {
+		(Some(from), Some(to), Some(amounts)) => (from, to, amounts),
+		_ => {
+			warn!(
+				"Malformed {} event in transaction {transaction:?}",
+				event.name
+			);
+			return vec![]
+		},
+	};
+
+	amounts
+		.into_iter()
+		.enumerate()
+		.map(|(batch_index, (token_id, value))| TokenTransfer {
+			transaction,
+			log_index,
+			batch_index: batch_index as u32,
+			token: log.address,
+			standard,
+			from,
+			to,
+			token_id,
+			value,
+		})
+		.collect()
+}
+
+// Create a link in database between the validators already known and the deposits emitted by
+// `transaction`
+//
+// Deposits for validators not known yet are linked once the validators are synced
+fn link_validators_to_depositor(
+	conn: &PgConnection,
+	chain: &str,
+	transaction: H256,
+) -> Result<(), Error> {
+	let rows = NewDeposits::link(conn, chain, Some(transaction))?;
+	info!("{rows} validator deposits linked to the transaction {transaction:?}");
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use std::{collections::BTreeMap, sync::Arc};
+
+	use diesel::OptionalExtension;
+	use futures::{executor::block_on, future::ready};
+	use kiln_postgres::ExecBlock;
+	use web3::types::H256;
+
+	use super::{search_fork_height, ExecutionSyncer};
+	use crate::{
+		abi_registry::AbiRegistry,
+		network::Network,
+		sync::{
+			test_utils::{test_pool, FakeNode},
+			DbSyncer, SyncError,
+		},
+		Error,
+	};
+
+	const CHAIN: &str = "reorg-test";
+
+	// Return the hashes of a branch of `len` blocks, sharing the blocks of the other branches of
+	// `test` up to `fork_height` excluded
+	//
+	// `test` keeps the hashes of concurrent tests apart, `fork` tells the branches apart
+	fn branch(test: u64, fork: u64, fork_height: u64, len: u64) -> Vec<H256> {
+		(0..len)
+			.map(|h| {
+				let n = if h < fork_height { h } else { (fork << 24) | h };
+				H256::from_low_u64_be((test << 48) | n)
+			})
+			.collect()
+	}
+
+	// Return the blocks of `chain` by height
+	fn stored(chain: Vec<H256>) -> BTreeMap<u64, H256> {
+		chain.into_iter().enumerate().map(|(h, hash)| (h as u64, hash)).collect()
+	}
+
+	// Run the fork search from `height` with `stored` in database and `canonical` on the node
+	fn fork_height(
+		height: u64,
+		stored: &BTreeMap<u64, H256>,
+		canonical: &[H256],
+	) -> Result<u64, Error> {
+		block_on(search_fork_height(
+			height,
+			|h| Ok(stored.range(..=h).next_back().map(|(n, hash)| (*n, *hash))),
+			|h| ready(Ok(canonical.get(h as usize).copied())),
+		))
+	}
+
+	#[test]
+	fn stored_chain_is_canonical() {
+		let chain = branch(1, 1, 10, 10);
+
+		assert_eq!(fork_height(9, &stored(chain.clone()), &chain).unwrap(), 10);
+	}
 
-	// Safe to unwrap because kiln is post Byzantum
-	let status = reciept.status.unwrap();
+	#[test]
+	fn returns_the_first_orphaned_height() {
+		let db = stored(branch(1, 1, 6, 10));
 
-	Ok(!status.is_zero())
+		assert_eq!(fork_height(9, &db, &branch(1, 2, 6, 12)).unwrap(), 6);
+	}
+
+	#[test]
+	fn skips_the_heights_never_synced() {
+		let mut with_gaps = stored(branch(1, 1, 4, 10));
+		for h in [2, 5, 6, 8] {
+			with_gaps.remove(&h);
+		}
+
+		assert_eq!(fork_height(9, &with_gaps, &branch(1, 2, 4, 10)).unwrap(), 4);
+		assert_eq!(fork_height(9, &with_gaps, &branch(1, 2, 2, 10)).unwrap(), 3);
+	}
+
+	#[test]
+	fn whole_stored_chain_is_orphaned() {
+		let db = stored(branch(1, 1, 0, 5));
+
+		assert_eq!(fork_height(4, &db, &branch(1, 2, 0, 5)).unwrap(), 0);
+	}
+
+	#[test]
+	fn nothing_stored() {
+		assert_eq!(
+			fork_height(4, &BTreeMap::new(), &branch(1, 1, 0, 5)).unwrap(),
+			5
+		);
+	}
+
+	#[test]
+	fn node_behind_the_stored_chain() {
+		let db = stored(branch(1, 1, 0, 10));
+		let res = fork_height(9, &db, &branch(1, 1, 0, 8));
+
+		assert!(matches!(
+			res,
+			Err(Error::Sync(SyncError::NothingAtHeight(9)))
+		));
+	}
+
+	fn new_syncer(node: &FakeNode) -> ExecutionSyncer {
+		ExecutionSyncer::new(
+			test_pool(),
+			node.client(),
+			Network::devnet(CHAIN),
+			1,
+			false,
+			Arc::new(AbiRegistry::default()),
+		)
+	}
+
+	async fn sync(syncer: &ExecutionSyncer, to: u64) {
+		for h in 0..=to {
+			syncer.create_new_entry(h).await.unwrap();
+		}
+	}
+
+	// Return the hash stored at every height up to `to` included
+	fn stored_hashes(syncer: &ExecutionSyncer, to: u64) -> Vec<Option<H256>> {
+		let conn = syncer.conn_pool.get().unwrap();
+		(0..=to)
+			.map(|h| ExecBlock::get(&conn, CHAIN, h).optional().unwrap().map(|b| b.hash()))
+			.collect()
+	}
+
+	#[tokio::test]
+	#[ignore = "requires a migrated database at DATABASE_URL"]
+	async fn delete_from_removes_the_blocks_from_height() {
+		let canonical = branch(2, 0, 5, 5);
+		let node = FakeNode::start(canonical.clone()).await;
+		let syncer = new_syncer(&node);
+		sync(&syncer, 4).await;
+
+		let deleted = ExecBlock::delete_from(&syncer.conn_pool.get().unwrap(), CHAIN, 2).unwrap();
+
+		assert_eq!(deleted, 3);
+		assert_eq!(
+			stored_hashes(&syncer, 4),
+			vec![Some(canonical[0]), Some(canonical[1]), None, None, None]
+		);
+	}
+
+	#[tokio::test]
+	#[ignore = "requires a migrated database at DATABASE_URL"]
+	async fn find_fork_height_returns_the_first_orphaned_height() {
+		let node = FakeNode::start(branch(3, 0, 6, 6)).await;
+		let syncer = new_syncer(&node);
+		sync(&syncer, 5).await;
+
+		assert_eq!(syncer.find_fork_height(5).await.unwrap(), 6);
+
+		node.switch_to(branch(3, 1, 3, 7));
+		assert_eq!(syncer.find_fork_height(5).await.unwrap(), 3);
+	}
+
+	#[tokio::test]
+	#[ignore = "requires a migrated database at DATABASE_URL"]
+	async fn handle_reorg_replaces_the_orphaned_blocks() {
+		let node = FakeNode::start(branch(4, 0, 6, 6)).await;
+		let syncer = new_syncer(&node);
+		sync(&syncer, 5).await;
+
+		let fork = branch(4, 1, 3, 7);
+		node.switch_to(fork.clone());
+		syncer.create_new_entry(6).await.unwrap();
+
+		assert_eq!(
+			stored_hashes(&syncer, 6),
+			fork.into_iter().map(Some).collect::<Vec<_>>()
+		);
+	}
+
+	#[tokio::test]
+	#[ignore = "requires a migrated database at DATABASE_URL"]
+	async fn entry_prefetched_from_an_orphaned_branch_is_refused() {
+		let node = FakeNode::start(branch(5, 0, 6, 6)).await;
+		let syncer = new_syncer(&node);
+		let stale_entry = syncer.fetch_entry(5).await.unwrap();
+		sync(&syncer, 3).await;
+
+		// The node switches branch at height 4 while height 5 was being fetched
+		let fork = branch(5, 1, 4, 6);
+		node.switch_to(fork.clone());
+		syncer.create_new_entry(4).await.unwrap();
+
+		let res = syncer.store_entry(5, stale_entry).await;
+		assert!(matches!(res, Err(Error::Sync(SyncError::StaleEntry(5)))));
+		assert_eq!(stored_hashes(&syncer, 5)[5], None);
+
+		syncer.create_new_entry(5).await.unwrap();
+		assert_eq!(
+			stored_hashes(&syncer, 5),
+			fork.into_iter().map(Some).collect::<Vec<_>>()
+		);
+	}
 }
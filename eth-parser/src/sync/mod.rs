@@ -1,12 +1,17 @@
 pub(crate) mod consensus_layer;
+pub(crate) mod deposits;
 pub(crate) mod execution_layer;
 pub(crate) mod syncer;
+#[cfg(test)]
+mod test_utils;
 pub(crate) mod validators;
 
 pub(crate) use consensus_layer::*;
 pub(crate) use execution_layer::*;
 pub(crate) use syncer::*;
 
+use web3::types::H256;
+
 #[derive(Debug)]
 pub enum SyncError {
 	/// Block not found at height
@@ -15,4 +20,14 @@ pub enum SyncError {
 	PendingBlock(u64),
 	/// The client did not return any validators
 	NoValidators,
+	/// The client did not return the committees of a slot
+	NoCommittees(u64),
+	/// The client did not return the sync committee of a slot
+	NoSyncCommittee(u64),
+	/// The client did not return the receipt of a transaction
+	NoReceipt(H256),
+	/// The entry was fetched from a branch the node left since
+	StaleEntry(u64),
+	/// The deposits of the block at a slot were not synced from the deposit contract yet
+	UnknownDeposits(u64),
 }
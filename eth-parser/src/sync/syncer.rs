@@ -1,51 +1,312 @@
-use std::fmt::Display;
+use std::{
+	fmt::Display,
+	future::Future,
+	ops::RangeInclusive,
+	time::{Duration, Instant},
+};
 
 use async_trait::async_trait;
-use log::{info, warn};
+use futures::{stream, StreamExt};
+use kiln_postgres::{FailedHeight, NewFailedHeight, PgConnectionPool};
+use log::{error, info, warn};
 
-use crate::Error;
+use crate::{shutdown::Shutdown, Error};
+
+/// Number of times `create_new_entry` is called for a height before giving up
+///
+/// Also the number of sync passes a recorded failed height is retried in before being abandoned
+const MAX_ATTEMPTS: u32 = 3;
+
+/// Delay before the first retry, doubled after each failed attempt
+const FIRST_RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// Number of stored entries between two throughput logs
+const THROUGHPUT_LOG_INTERVAL: u64 = 100;
 
 /// Sugar around storing chain block in database
 ///
 /// Allow for full control over how to pull entry and what to store.
-/// Only take care of the looping part.
+/// Only take care of the looping part: entries are fetched concurrently, up to `max_in_flight`
+/// requests to the node, and stored one at a time in height order.
 #[async_trait]
 pub trait DbSyncer: Display {
 	type NodeClient;
 
+	/// Data fetched from the node for one height, ready to be stored
+	type Entry: Send;
+
 	/// Bump database
 	///
-	/// Call `create_new_entry` for every height between `from` and `to` included
+	/// Retry the heights that previously failed, then call `create_new_entry` for every height
+	/// between `from` and `to` included
 	///
-	/// If from is None, the height following the highest stored height in db will be used.
+	/// If from is None, the height following the highest stored or failed height in db will be
+	/// used, so that a failed height is only retried by `retry_failed_heights`.
 	/// If db empty from will be 0.
-	async fn bump(&self, from: Option<u64>, to: u64) -> Result<u64, Error> {
-		let from = from.unwrap_or_else(|| self.get_db_height().map_or(0, |slot| slot + 1));
+	///
+	/// Stop before the next height once `shutdown` is requested.
+	/// Return the last height processed.
+	async fn bump(&self, from: Option<u64>, to: u64, shutdown: &Shutdown) -> Result<u64, Error> {
+		self.retry_failed_heights(false, shutdown).await?;
+
+		let from = match from {
+			Some(from) => from,
+			None => {
+				let opt_db_height = self.get_db_height().ok();
+				let opt_failed_height = self.list_failed_heights()?.last().map(|f| f.height());
+				opt_db_height.max(opt_failed_height).map_or(0, |h| h + 1)
+			},
+		};
+
+		self.sync_range(from..=to, shutdown).await
+	}
 
-		info!("{self}: Bumping database from heigth {from} to {to}",);
+	/// Fill the holes left in database
+	///
+	/// Retry the heights that previously failed, abandoned ones included, then sync again every
+	/// range of heights missing between the lowest and the highest stored entries.
+	///
+	/// Return the number of heights processed
+	async fn repair(&self, shutdown: &Shutdown) -> Result<u64, Error> {
+		self.retry_failed_heights(true, shutdown).await?;
+
+		let missing_ranges = self.list_missing_ranges()?;
+		info!("{self}: {} missing ranges to repair", missing_ranges.len());
 
-		for height in from..=to {
-			match self.create_new_entry(height).await {
+		let mut repaired = 0;
+		for range in missing_ranges {
+			if shutdown.is_requested() {
+				break
+			}
+			repaired += range.end() - range.start() + 1;
+			self.sync_range(range, shutdown).await?;
+		}
+
+		Ok(repaired)
+	}
+
+	/// Fetch and store every height of `range`
+	///
+	/// Up to `max_in_flight` heights are fetched concurrently, but entries are always stored in
+	/// height order. Heights that still fail after `MAX_ATTEMPTS` are recorded to be retried later,
+	/// their error is not returned.
+	///
+	/// Stop before the next height once `shutdown` is requested.
+	/// Return the last height processed.
+	async fn sync_range(
+		&self,
+		range: RangeInclusive<u64>,
+		shutdown: &Shutdown,
+	) -> Result<u64, Error> {
+		let (from, to) = range.into_inner();
+
+		info!(
+			"{self}: Bumping database from heigth {from} to {to}, {} requests in flight",
+			self.max_in_flight()
+		);
+
+		let started_at = Instant::now();
+		let mut processed: u64 = 0;
+
+		// `buffered` yields the fetched entries in the order of the heights
+		let mut entries = stream::iter(from..=to)
+			.map(|height| async move {
+				let res = with_retries(self, height, || self.fetch_entry(height)).await;
+				(height, res)
+			})
+			.buffered(self.max_in_flight());
+
+		while let Some((height, res_entry)) = entries.next().await {
+			if shutdown.is_requested() {
+				info!("{self}: Shutdown requested, stopping before height {height}");
+				return Ok(height.saturating_sub(1))
+			}
+
+			let res = match res_entry {
+				Ok(entry) => match self.store_entry(height, entry).await {
+					Ok(()) => Ok(()),
+					Err(err) => {
+						warn!("{self}: Failed to store entry at height {height}, retrying: {err}");
+						self.create_new_entry_with_retries(height).await
+					},
+				},
+				Err(err) => Err(err),
+			};
+
+			match res {
 				Ok(()) => info!("{self}: Saved entry at height {height}"),
-				Err(err) => warn!("{self}: Failed to create enty at height {height}: {err}"),
+				Err(err) => {
+					warn!("{self}: Failed to create enty at height {height}: {err}");
+					self.record_failed_height(height, &err);
+				},
+			}
+
+			processed += 1;
+			if processed % THROUGHPUT_LOG_INTERVAL == 0 {
+				log_throughput(self, processed, started_at);
 			}
 		}
 
+		log_throughput(self, processed, started_at);
+
 		Ok(to)
 	}
 
+	/// Retry once every height previously recorded as failed
+	///
+	/// Successful heights are removed from the record, others see their attempt count increased.
+	/// Heights that failed `MAX_ATTEMPTS` times are abandoned, unless `include_abandoned` is set.
+	async fn retry_failed_heights(
+		&self,
+		include_abandoned: bool,
+		shutdown: &Shutdown,
+	) -> Result<(), Error> {
+		let syncer = self.to_string();
+		let failed_heights = self.list_failed_heights()?;
+
+		for failed in failed_heights {
+			if shutdown.is_requested() {
+				break
+			}
+
+			let height = failed.height();
+			if failed.attempts() >= MAX_ATTEMPTS && !include_abandoned {
+				warn!(
+					"{self}: Height {height} abandoned after {} attempts, run a repair to retry it",
+					failed.attempts()
+				);
+				continue
+			}
+			info!(
+				"{self}: Retrying height {height}, failed {} times",
+				failed.attempts()
+			);
+			match self.create_new_entry_with_retries(height).await {
+				Ok(()) => {
+					FailedHeight::delete(
+						&self.conn_pool().get().unwrap(),
+						self.chain(),
+						&syncer,
+						height,
+					)?;
+					info!("{self}: Saved entry at previously failed height {height}");
+				},
+				Err(err) => {
+					warn!("{self}: Failed again to create entry at height {height}: {err}");
+					self.record_failed_height(height, &err);
+				},
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Call `create_new_entry` up to `MAX_ATTEMPTS` times, with an exponential backoff
+	async fn create_new_entry_with_retries(&self, height: u64) -> Result<(), Error> {
+		with_retries(self, height, || self.create_new_entry(height)).await
+	}
+
+	/// Return the heights recorded as failed, lowest first
+	fn list_failed_heights(&self) -> Result<Vec<FailedHeight>, Error> {
+		let failed_heights = FailedHeight::list(
+			&self.conn_pool().get().unwrap(),
+			self.chain(),
+			&self.to_string(),
+		)?;
+
+		Ok(failed_heights)
+	}
+
+	/// Store `height` as failed so that it is retried on the next bump
+	///
+	/// Each sync pass that failed to store the height counts as one attempt
+	fn record_failed_height(&self, height: u64, err: &Error) {
+		let failed =
+			NewFailedHeight::new(self.chain(), self.to_string(), height, 1, err.to_string());
+		if let Err(db_err) = failed.upsert(&self.conn_pool().get().unwrap()) {
+			error!("{self}: Failed to record failed height {height}: {db_err}");
+		}
+	}
+
+	/// Return the database connection pool
+	fn conn_pool(&self) -> &PgConnectionPool;
+
+	/// Return the name of the chain stored on every row
+	fn chain(&self) -> &str;
+
 	/// Return a instance of the node client
 	fn node_client(&self) -> Self::NodeClient;
 
+	/// Return the maximum number of heights fetched concurrently from the node
+	fn max_in_flight(&self) -> usize;
+
 	/// Return the database head height
 	fn get_db_height(&self) -> Result<u64, Error>;
 
+	/// Return the ranges of heights missing in database
+	fn list_missing_ranges(&self) -> Result<Vec<RangeInclusive<u64>>, Error>;
+
 	/// Register a new entry in database
 	///
 	/// # Arguments
 	/// * `height`: height of the block to create
 	///
-	/// Called internaly by `keep_in_sync`.
-	/// Should fetch data from the node and store them in database.
-	async fn create_new_entry(&self, height: u64) -> Result<(), Error>;
+	/// Fetch the entry from the node and store it right away.
+	async fn create_new_entry(&self, height: u64) -> Result<(), Error> {
+		let entry = self.fetch_entry(height).await?;
+
+		self.store_entry(height, entry).await
+	}
+
+	/// Fetch the data of a new entry from the node
+	///
+	/// # Arguments
+	/// * `height`: height of the block to fetch
+	///
+	/// Called concurrently for several heights, should not write anything in database.
+	async fn fetch_entry(&self, height: u64) -> Result<Self::Entry, Error>;
+
+	/// Store a fetched entry in database
+	///
+	/// # Arguments
+	/// * `height`: height of the block to store
+	/// * `entry`: data returned by `fetch_entry` for this height
+	///
+	/// Called in height order, one height at a time.
+	async fn store_entry(&self, height: u64, entry: Self::Entry) -> Result<(), Error>;
+}
+
+// Call `f` up to `MAX_ATTEMPTS` times, with an exponential backoff
+async fn with_retries<S, T, F, Fut>(syncer: &S, height: u64, f: F) -> Result<T, Error>
+where
+	S: Display + ?Sized,
+	F: Fn() -> Fut,
+	Fut: Future<Output = Result<T, Error>>,
+{
+	let mut delay = FIRST_RETRY_DELAY;
+	let mut attempt = 1;
+
+	loop {
+		match f().await {
+			Ok(v) => return Ok(v),
+			Err(err) if attempt >= MAX_ATTEMPTS => return Err(err),
+			Err(err) => {
+				warn!("{syncer}: Attempt {attempt} at height {height} failed, retrying: {err}");
+				tokio::time::sleep(delay).await;
+				delay *= 2;
+				attempt += 1;
+			},
+		}
+	}
+}
+
+// Log the number of entries processed per second since `started_at`
+fn log_throughput<S: Display + ?Sized>(syncer: &S, processed: u64, started_at: Instant) {
+	let elapsed = started_at.elapsed().as_secs_f64();
+	if elapsed > 0.0 {
+		info!(
+			"{syncer}: {processed} entries processed in {elapsed:.1}s ({:.2} entries/s)",
+			processed as f64 / elapsed
+		);
+	}
 }
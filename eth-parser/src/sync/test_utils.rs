@@ -0,0 +1,190 @@
+use std::{
+	net::SocketAddr,
+	sync::{Arc, Mutex},
+};
+
+use diesel::{
+	r2d2::{self, ConnectionManager, CustomizeConnection},
+	Connection, PgConnection,
+};
+use kiln_postgres::PgConnectionPool;
+use serde_json::{json, Value};
+use tokio::{
+	io::{AsyncReadExt, AsyncWriteExt},
+	net::{TcpListener, TcpStream},
+};
+use web3::{
+	transports::Http,
+	types::{H160, H256},
+	Web3,
+};
+
+/// Return a pool holding a single connection to the database at `DATABASE_URL`
+///
+/// Everything written through the pool is rolled back once it is dropped. The database must be
+/// migrated.
+pub fn test_pool() -> PgConnectionPool {
+	dotenv::dotenv().ok();
+	let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+
+	r2d2::Pool::builder()
+		.max_size(1)
+		.connection_customizer(Box::new(TestTransaction))
+		.build(ConnectionManager::<PgConnection>::new(database_url))
+		.unwrap()
+}
+
+#[derive(Debug)]
+struct TestTransaction;
+
+impl CustomizeConnection<PgConnection, r2d2::Error> for TestTransaction {
+	fn on_acquire(&self, conn: &mut PgConnection) -> Result<(), r2d2::Error> {
+		conn.begin_test_transaction().map_err(r2d2::Error::QueryError)
+	}
+}
+
+/// An execution node serving a chain of empty blocks over JSON-RPC
+///
+/// Only `eth_getBlockByNumber` is answered. The chain can be switched to another branch at any
+/// time to simulate a reorg.
+#[derive(Clone)]
+pub struct FakeNode {
+	/// Hash of the block at each height
+	chain: Arc<Mutex<Vec<H256>>>,
+	addr: SocketAddr,
+}
+
+impl FakeNode {
+	/// Start a node serving `chain` on a random local port
+	pub async fn start(chain: Vec<H256>) -> FakeNode {
+		let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+		let node = FakeNode {
+			chain: Arc::new(Mutex::new(chain)),
+			addr: listener.local_addr().unwrap(),
+		};
+
+		let served = node.clone();
+		tokio::spawn(async move {
+			while let Ok((stream, _)) = listener.accept().await {
+				tokio::spawn(served.clone().serve(stream));
+			}
+		});
+
+		node
+	}
+
+	/// Return a client connected to the node
+	pub fn client(&self) -> Web3<Http> {
+		Web3::new(Http::new(&format!("http://{}", self.addr)).unwrap())
+	}
+
+	/// Serve `chain` from now on
+	pub fn switch_to(&self, chain: Vec<H256>) {
+		*self.chain.lock().unwrap() = chain;
+	}
+
+	// Answer the requests of a connection until it is closed
+	async fn serve(self, mut stream: TcpStream) {
+		let mut buf = vec![];
+		loop {
+			let (head_len, body_len) = loop {
+				match parse_head(&buf) {
+					Some((head_len, body_len)) if buf.len() >= head_len + body_len =>
+						break (head_len, body_len),
+					_ => {},
+				}
+				let mut chunk = [0u8; 4096];
+				match stream.read(&mut chunk).await {
+					Ok(0) | Err(_) => return,
+					Ok(n) => buf.extend_from_slice(&chunk[..n]),
+				}
+			};
+
+			let request: Value =
+				serde_json::from_slice(&buf[head_len..head_len + body_len]).unwrap();
+			buf.drain(..head_len + body_len);
+			let response = match request {
+				Value::Array(calls) => Value::Array(calls.iter().map(|c| self.answer(c)).collect()),
+				call => self.answer(&call),
+			};
+
+			let body = response.to_string();
+			let head = format!(
+				"HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n",
+				body.len()
+			);
+			if stream.write_all(head.as_bytes()).await.is_err()
+				|| stream.write_all(body.as_bytes()).await.is_err()
+			{
+				return
+			}
+		}
+	}
+
+	// Answer a single JSON-RPC call
+	fn answer(&self, call: &Value) -> Value {
+		let result = match call["method"].as_str() {
+			Some("eth_getBlockByNumber") => call["params"][0]
+				.as_str()
+				.and_then(|h| u64::from_str_radix(h.trim_start_matches("0x"), 16).ok())
+				.map_or(Value::Null, |h| self.block(h)),
+			_ => Value::Null,
+		};
+
+		json!({ "jsonrpc": "2.0", "id": call["id"], "result": result })
+	}
+
+	// Return the block at `height`, null if the chain is shorter
+	fn block(&self, height: u64) -> Value {
+		let chain = self.chain.lock().unwrap();
+		let hash = match chain.get(height as usize) {
+			Some(h) => *h,
+			None => return Value::Null,
+		};
+		let parent_hash = match height {
+			0 => H256::zero(),
+			h => chain[h as usize - 1],
+		};
+
+		json!({
+			"hash": hash,
+			"parentHash": parent_hash,
+			"sha3Uncles": H256::zero(),
+			"miner": H160::zero(),
+			"stateRoot": H256::zero(),
+			"transactionsRoot": H256::zero(),
+			"receiptsRoot": H256::zero(),
+			"number": format!("{height:#x}"),
+			"gasUsed": "0x0",
+			"gasLimit": "0x1c9c380",
+			"baseFeePerGas": "0x7",
+			"extraData": "0x",
+			"logsBloom": format!("0x{}", "00".repeat(256)),
+			"timestamp": format!("{:#x}", 12 * height),
+			"difficulty": "0x0",
+			"totalDifficulty": "0x0",
+			"sealFields": [],
+			"uncles": [],
+			"transactions": [],
+			"size": "0x200",
+			"mixHash": H256::zero(),
+			"nonce": "0x0000000000000000",
+		})
+	}
+}
+
+// Return the length of the head of a buffered HTTP request and the length of its body
+//
+// Return None if the head is not fully buffered yet
+fn parse_head(buf: &[u8]) -> Option<(usize, usize)> {
+	let head_len = buf.windows(4).position(|w| w == b"\r\n\r\n")? + 4;
+	let head = std::str::from_utf8(&buf[..head_len]).ok()?;
+	let body_len = head
+		.lines()
+		.filter_map(|l| l.split_once(':'))
+		.find(|(k, _)| k.eq_ignore_ascii_case("content-length"))
+		.and_then(|(_, v)| v.trim().parse().ok())
+		.unwrap_or(0);
+
+	Some((head_len, body_len))
+}
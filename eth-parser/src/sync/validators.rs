@@ -1,5 +1,12 @@
-use eth2::BeaconNodeHttpClient;
-use kiln_postgres::{NewValidators, PgConnectionPool};
+use diesel::Connection;
+use eth2::{
+	types::{Epoch, EthSpec, MainnetEthSpec},
+	BeaconNodeHttpClient,
+};
+use kiln_postgres::{
+	NewDeposits, NewValidator, NewValidatorBalance, NewValidatorBalances, NewValidators,
+	PgConnectionPool,
+};
 use log::info;
 
 use crate::{client_consensus, error::Error};
@@ -7,9 +14,13 @@ use crate::{client_consensus, error::Error};
 use super::SyncError;
 
 /// Update db validators
+///
+/// Every status change is logged along the epoch of `slot`, and the deposits made for new
+/// validators are linked to them
 pub async fn update_validators(
 	conn_pool: PgConnectionPool,
 	client: &BeaconNodeHttpClient,
+	chain: &str,
 	slot: u64,
 ) -> Result<(), Error> {
 	info!("syncing db with validators at slot {slot}");
@@ -18,8 +29,64 @@ pub async fn update_validators(
 		.await?
 		.ok_or(SyncError::NoValidators)?;
 
-	let new_validators = NewValidators::from_iter(validators.into_iter().map(|v| v.into()));
-	new_validators.batch_upsert(&conn_pool.get().unwrap())?;
+	let new_validators =
+		NewValidators::from_iter(validators.into_iter().map(|v| NewValidator::new(chain, v)));
+
+	// Log the status changes before the stored statuses are overwritten
+	let conn = conn_pool.get().unwrap();
+	let (status_changes, linked_deposits) = conn.transaction::<_, Error, _>(|| {
+		let status_changes = new_validators.status_changes(&conn, chain, epoch_of_slot(slot))?;
+		status_changes.batch_insert(&conn)?;
+		new_validators.batch_upsert(&conn)?;
+		let linked_deposits = NewDeposits::link(&conn, chain, None)?;
+
+		Ok((status_changes, linked_deposits))
+	})?;
+	info!("{} validators changed status", status_changes.len());
+	info!("{linked_deposits} deposits linked to their validator");
 
 	Ok(())
 }
+
+/// Store a snapshot of every validator balance at the start of `epoch`
+///
+/// Balances already stored for this epoch are kept.
+/// Return the number of new balances stored
+pub async fn record_balances(
+	conn_pool: PgConnectionPool,
+	client: &BeaconNodeHttpClient,
+	chain: &str,
+	epoch: u64,
+) -> Result<usize, Error> {
+	let slot = epoch_start_slot(epoch);
+	info!("recording validator balances of epoch {epoch} at slot {slot}");
+
+	let validators = client_consensus::get_validators_at_slot(client, slot)
+		.await?
+		.ok_or(SyncError::NoValidators)?;
+
+	let new_balances = NewValidatorBalances::from_iter(
+		validators.iter().map(|v| NewValidatorBalance::new(chain, epoch, v)),
+	);
+	let inserted = new_balances.batch_insert(&conn_pool.get().unwrap())?;
+
+	Ok(inserted)
+}
+
+/// Number of epochs a sync committee stays in charge, on the mainnet preset
+const EPOCHS_PER_SYNC_COMMITTEE_PERIOD: u64 = 256;
+
+/// Return the epoch `slot` belongs to
+pub fn epoch_of_slot(slot: u64) -> u64 {
+	slot / MainnetEthSpec::slots_per_epoch()
+}
+
+/// Return the sync committee period `slot` belongs to
+pub fn sync_committee_period_of_slot(slot: u64) -> u64 {
+	epoch_of_slot(slot) / EPOCHS_PER_SYNC_COMMITTEE_PERIOD
+}
+
+// Return the first slot of `epoch`
+fn epoch_start_slot(epoch: u64) -> u64 {
+	Epoch::new(epoch).start_slot(MainnetEthSpec::slots_per_epoch()).as_u64()
+}
@@ -0,0 +1,7 @@
+-- Your SQL goes here
+
+ALTER TABLE slots
+ADD COLUMN finalized BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE INDEX slots_finalized_idx
+ON slots(finalized);
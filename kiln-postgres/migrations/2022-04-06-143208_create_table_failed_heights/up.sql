@@ -0,0 +1,9 @@
+-- Your SQL goes here
+
+CREATE TABLE failed_heights (
+    syncer VARCHAR NOT NULL,
+    height BIGINT NOT NULL,
+    attempts INTEGER NOT NULL,
+    error VARCHAR NOT NULL,
+    PRIMARY KEY (syncer, height)
+);
@@ -0,0 +1,4 @@
+-- Your SQL goes here
+
+ALTER TABLE slots
+ADD COLUMN missed BOOLEAN NOT NULL DEFAULT FALSE;
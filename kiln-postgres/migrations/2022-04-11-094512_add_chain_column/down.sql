@@ -0,0 +1,32 @@
+-- This file should undo anything in `up.sql`
+
+ALTER TABLE failed_heights
+DROP CONSTRAINT failed_heights_pkey;
+ALTER TABLE failed_heights
+ADD PRIMARY KEY (syncer, height);
+ALTER TABLE failed_heights
+DROP COLUMN chain;
+
+ALTER TABLE validators
+DROP CONSTRAINT validators_pkey;
+ALTER TABLE validators
+ADD PRIMARY KEY ("index");
+ALTER TABLE validators
+DROP COLUMN chain;
+
+DROP INDEX transactions_chain_idx;
+ALTER TABLE transactions
+DROP COLUMN chain;
+
+DROP INDEX number_idx;
+CREATE INDEX number_idx
+ON execution_blocks("number");
+ALTER TABLE execution_blocks
+DROP COLUMN chain;
+
+ALTER TABLE slots
+DROP CONSTRAINT slots_pkey;
+ALTER TABLE slots
+ADD PRIMARY KEY (height);
+ALTER TABLE slots
+DROP COLUMN chain;
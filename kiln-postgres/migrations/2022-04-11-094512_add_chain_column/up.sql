@@ -0,0 +1,45 @@
+-- Your SQL goes here
+
+-- Rows indexed before multiple networks were supported all come from Kiln
+
+ALTER TABLE slots
+ADD COLUMN chain VARCHAR NOT NULL DEFAULT 'kiln';
+ALTER TABLE slots
+ALTER COLUMN chain DROP DEFAULT;
+ALTER TABLE slots
+DROP CONSTRAINT slots_pkey;
+ALTER TABLE slots
+ADD PRIMARY KEY (chain, height);
+
+ALTER TABLE execution_blocks
+ADD COLUMN chain VARCHAR NOT NULL DEFAULT 'kiln';
+ALTER TABLE execution_blocks
+ALTER COLUMN chain DROP DEFAULT;
+DROP INDEX number_idx;
+CREATE INDEX number_idx
+ON execution_blocks(chain, "number");
+
+ALTER TABLE transactions
+ADD COLUMN chain VARCHAR NOT NULL DEFAULT 'kiln';
+ALTER TABLE transactions
+ALTER COLUMN chain DROP DEFAULT;
+CREATE INDEX transactions_chain_idx
+ON transactions(chain);
+
+ALTER TABLE validators
+ADD COLUMN chain VARCHAR NOT NULL DEFAULT 'kiln';
+ALTER TABLE validators
+ALTER COLUMN chain DROP DEFAULT;
+ALTER TABLE validators
+DROP CONSTRAINT validators_pkey;
+ALTER TABLE validators
+ADD PRIMARY KEY (chain, "index");
+
+ALTER TABLE failed_heights
+ADD COLUMN chain VARCHAR NOT NULL DEFAULT 'kiln';
+ALTER TABLE failed_heights
+ALTER COLUMN chain DROP DEFAULT;
+ALTER TABLE failed_heights
+DROP CONSTRAINT failed_heights_pkey;
+ALTER TABLE failed_heights
+ADD PRIMARY KEY (chain, syncer, height);
@@ -0,0 +1,12 @@
+-- Your SQL goes here
+
+CREATE TABLE validator_balances (
+    validator_index BIGINT NOT NULL,
+    epoch BIGINT NOT NULL,
+    balance BIGINT NOT NULL,
+    effective_balance BIGINT NOT NULL,
+    chain VARCHAR NOT NULL,
+    PRIMARY KEY (chain, validator_index, epoch)
+);
+
+CREATE INDEX validator_balances_epoch_idx ON validator_balances (chain, epoch);
@@ -0,0 +1,13 @@
+-- Your SQL goes here
+
+-- Statuses only move forward, a validator enters each of them at most once
+CREATE TABLE validator_status_changes (
+    validator_index BIGINT NOT NULL,
+    epoch BIGINT NOT NULL,
+    old_status VARCHAR,
+    new_status VARCHAR NOT NULL,
+    chain VARCHAR NOT NULL,
+    PRIMARY KEY (chain, validator_index, new_status)
+);
+
+CREATE INDEX validator_status_changes_status_idx ON validator_status_changes (chain, new_status, epoch);
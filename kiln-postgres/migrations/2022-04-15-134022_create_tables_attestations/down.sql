@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+
+DROP TABLE attestation_inclusions;
+DROP TABLE attestation_duties;
+ALTER TABLE slots DROP COLUMN block_root;
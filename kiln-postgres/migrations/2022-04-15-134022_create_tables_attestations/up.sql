@@ -0,0 +1,32 @@
+-- Your SQL goes here
+
+-- Root of the beacon block, used to check the votes of the attestations
+ALTER TABLE slots ADD COLUMN block_root BYTEA;
+
+-- Committee assignment of every validator, one per epoch
+CREATE TABLE attestation_duties (
+    validator_index BIGINT NOT NULL,
+    epoch BIGINT NOT NULL,
+    slot BIGINT NOT NULL,
+    committee_index BIGINT NOT NULL,
+    chain VARCHAR NOT NULL,
+    PRIMARY KEY (chain, validator_index, epoch)
+);
+
+CREATE INDEX attestation_duties_slot_idx ON attestation_duties (chain, slot);
+
+-- Every block a validator attestation was included in
+CREATE TABLE attestation_inclusions (
+    validator_index BIGINT NOT NULL,
+    epoch BIGINT NOT NULL,
+    inclusion_slot BIGINT NOT NULL,
+    beacon_block_root BYTEA NOT NULL,
+    source_epoch BIGINT NOT NULL,
+    source_root BYTEA NOT NULL,
+    target_epoch BIGINT NOT NULL,
+    target_root BYTEA NOT NULL,
+    chain VARCHAR NOT NULL,
+    PRIMARY KEY (chain, validator_index, epoch, inclusion_slot)
+);
+
+CREATE INDEX attestation_inclusions_inclusion_slot_idx ON attestation_inclusions (chain, inclusion_slot);
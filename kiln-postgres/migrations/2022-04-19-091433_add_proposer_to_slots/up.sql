@@ -0,0 +1,8 @@
+-- Your SQL goes here
+
+-- The proposer is also known for missed slots, from the proposer duties
+ALTER TABLE slots ADD COLUMN proposer_index BIGINT;
+ALTER TABLE slots ADD COLUMN graffiti VARCHAR;
+ALTER TABLE slots ADD COLUMN randao_reveal BYTEA;
+
+CREATE INDEX slots_proposer_idx ON slots (chain, proposer_index);
@@ -0,0 +1,11 @@
+-- Your SQL goes here
+
+-- The proposer of the block including the evidence acts as whistleblower
+CREATE TABLE slashings (
+    validator_index BIGINT NOT NULL,
+    slot BIGINT NOT NULL,
+    slashing_type VARCHAR NOT NULL,
+    whistleblower_index BIGINT NOT NULL,
+    chain VARCHAR NOT NULL,
+    PRIMARY KEY (chain, validator_index, slot, slashing_type)
+);
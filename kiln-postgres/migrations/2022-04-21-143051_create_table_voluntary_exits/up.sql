@@ -0,0 +1,11 @@
+-- Your SQL goes here
+
+CREATE TABLE voluntary_exits (
+    validator_index BIGINT NOT NULL,
+    exit_epoch BIGINT NOT NULL,
+    inclusion_slot BIGINT NOT NULL,
+    chain VARCHAR NOT NULL,
+    PRIMARY KEY (chain, validator_index)
+);
+
+CREATE INDEX voluntary_exits_inclusion_slot_idx ON voluntary_exits (chain, inclusion_slot);
@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+
+DROP TABLE sync_participations;
+DROP TABLE sync_committee_members;
@@ -0,0 +1,22 @@
+-- Your SQL goes here
+
+-- Members of the sync committee of every period, a validator may hold several positions
+CREATE TABLE sync_committee_members (
+    period BIGINT NOT NULL,
+    position INTEGER NOT NULL,
+    validator_index BIGINT NOT NULL,
+    chain VARCHAR NOT NULL,
+    PRIMARY KEY (chain, period, position)
+);
+
+-- Whether each member signed the sync aggregate of a block
+CREATE TABLE sync_participations (
+    slot BIGINT NOT NULL,
+    position INTEGER NOT NULL,
+    validator_index BIGINT NOT NULL,
+    participated BOOLEAN NOT NULL,
+    chain VARCHAR NOT NULL,
+    PRIMARY KEY (chain, slot, position)
+);
+
+CREATE INDEX sync_participations_validator_idx ON sync_participations (chain, validator_index, slot);
@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+
+DROP TABLE deposit_reconciliations;
+DROP TABLE consensus_deposits;
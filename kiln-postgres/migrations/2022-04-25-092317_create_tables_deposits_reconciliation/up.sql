@@ -0,0 +1,25 @@
+-- Your SQL goes here
+
+-- Deposits processed by the beacon chain, `deposit_index` is the index in the deposit contract
+CREATE TABLE consensus_deposits (
+    deposit_index BIGINT NOT NULL,
+    inclusion_slot BIGINT NOT NULL,
+    pubkey VARCHAR NOT NULL,
+    withdrawal_credentials BYTEA NOT NULL,
+    amount BIGINT NOT NULL,
+    signature BYTEA NOT NULL,
+    chain VARCHAR NOT NULL,
+    PRIMARY KEY (chain, deposit_index)
+);
+
+CREATE INDEX consensus_deposits_inclusion_slot_idx ON consensus_deposits (chain, inclusion_slot);
+CREATE INDEX consensus_deposits_pubkey_idx ON consensus_deposits (chain, pubkey);
+
+-- Outcome of every successful call to the deposit contract
+CREATE TABLE deposit_reconciliations (
+    transaction_hash BYTEA NOT NULL,
+    deposit_index BIGINT,
+    status VARCHAR NOT NULL,
+    chain VARCHAR NOT NULL,
+    PRIMARY KEY (chain, transaction_hash)
+);
@@ -0,0 +1,8 @@
+-- This file should undo anything in `up.sql`
+
+DELETE FROM deposit_reconciliations;
+ALTER TABLE deposit_reconciliations DROP CONSTRAINT deposit_reconciliations_pkey;
+ALTER TABLE deposit_reconciliations ALTER COLUMN deposit_index DROP NOT NULL;
+ALTER TABLE deposit_reconciliations ADD PRIMARY KEY (chain, transaction_hash);
+
+DROP TABLE execution_deposits;
@@ -0,0 +1,22 @@
+-- Your SQL goes here
+
+-- DepositEvent logs emitted by the deposit contract, whoever called it
+CREATE TABLE execution_deposits (
+    deposit_index BIGINT NOT NULL,
+    transaction_hash BYTEA NOT NULL,
+    log_index BIGINT NOT NULL,
+    pubkey VARCHAR NOT NULL,
+    withdrawal_credentials BYTEA NOT NULL,
+    amount BIGINT NOT NULL,
+    signature BYTEA NOT NULL,
+    chain VARCHAR NOT NULL,
+    PRIMARY KEY (chain, deposit_index)
+);
+
+CREATE INDEX execution_deposits_transaction_idx ON execution_deposits (transaction_hash);
+
+-- A transaction may emit several deposits, reconciliations are now made per deposit index
+DELETE FROM deposit_reconciliations;
+ALTER TABLE deposit_reconciliations DROP CONSTRAINT deposit_reconciliations_pkey;
+ALTER TABLE deposit_reconciliations ALTER COLUMN deposit_index SET NOT NULL;
+ALTER TABLE deposit_reconciliations ADD PRIMARY KEY (chain, deposit_index);
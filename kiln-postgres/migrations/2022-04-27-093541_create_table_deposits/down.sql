@@ -0,0 +1,11 @@
+-- This file should undo anything in `up.sql`
+
+ALTER TABLE validators ADD COLUMN deposit_transaction BYTEA REFERENCES transactions(hash);
+
+UPDATE validators v SET deposit_transaction = (
+    SELECT d.transaction_hash FROM deposits d
+    WHERE d.chain = v.chain AND d.validator_index = v."index"
+    ORDER BY d.deposit_index DESC NULLS LAST LIMIT 1
+);
+
+DROP TABLE deposits;
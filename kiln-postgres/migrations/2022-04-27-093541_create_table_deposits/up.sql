@@ -0,0 +1,32 @@
+-- Your SQL goes here
+
+-- A validator can be topped up and a transaction can register several validators
+--
+-- The links made before the DepositEvent logs were decoded have no deposit index nor amount,
+-- they are replaced once the syncers link the deposit of their transaction
+CREATE TABLE deposits (
+    id BIGSERIAL PRIMARY KEY,
+    deposit_index BIGINT,
+    validator_index BIGINT NOT NULL,
+    transaction_hash BYTEA NOT NULL REFERENCES transactions(hash),
+    amount BIGINT,
+    chain VARCHAR NOT NULL,
+    UNIQUE (chain, deposit_index)
+);
+
+CREATE INDEX deposits_validator_idx ON deposits (chain, validator_index);
+CREATE INDEX deposits_transaction_idx ON deposits (transaction_hash);
+
+INSERT INTO deposits (deposit_index, validator_index, transaction_hash, amount, chain)
+SELECT d.deposit_index, v."index", d.transaction_hash, d.amount, d.chain
+FROM execution_deposits d
+JOIN validators v ON v.chain = d.chain AND v.pubkey = d.pubkey;
+
+INSERT INTO deposits (deposit_index, validator_index, transaction_hash, amount, chain)
+SELECT NULL, v."index", v.deposit_transaction, NULL, v.chain
+FROM validators v
+WHERE v.deposit_transaction IS NOT NULL AND NOT EXISTS (
+    SELECT 1 FROM deposits d WHERE d.chain = v.chain AND d.validator_index = v."index"
+);
+
+ALTER TABLE validators DROP COLUMN deposit_transaction;
@@ -0,0 +1,8 @@
+-- This file should undo anything in `up.sql`
+
+DROP TABLE logs;
+
+ALTER TABLE transactions DROP COLUMN contract_address;
+ALTER TABLE transactions DROP COLUMN effective_gas_price;
+ALTER TABLE transactions DROP COLUMN cumulative_gas_used;
+ALTER TABLE transactions DROP COLUMN gas_used;
@@ -0,0 +1,22 @@
+-- Your SQL goes here
+
+ALTER TABLE transactions ADD COLUMN gas_used BIGINT;
+ALTER TABLE transactions ADD COLUMN cumulative_gas_used BIGINT;
+ALTER TABLE transactions ADD COLUMN effective_gas_price BYTEA;
+ALTER TABLE transactions ADD COLUMN contract_address BYTEA;
+
+CREATE TABLE logs (
+    transaction_hash BYTEA NOT NULL REFERENCES transactions(hash),
+    log_index BIGINT NOT NULL,
+    "address" BYTEA NOT NULL,
+    topic0 BYTEA,
+    topic1 BYTEA,
+    topic2 BYTEA,
+    topic3 BYTEA,
+    "data" BYTEA NOT NULL,
+    chain VARCHAR NOT NULL,
+    PRIMARY KEY (transaction_hash, log_index)
+);
+
+CREATE INDEX logs_address_idx ON logs ("address");
+CREATE INDEX logs_topic0_idx ON logs (topic0);
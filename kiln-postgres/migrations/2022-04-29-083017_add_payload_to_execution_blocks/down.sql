@@ -0,0 +1,10 @@
+-- This file should undo anything in `up.sql`
+
+ALTER TABLE execution_blocks DROP COLUMN prev_randao;
+ALTER TABLE execution_blocks DROP COLUMN logs_bloom;
+ALTER TABLE execution_blocks DROP COLUMN extra_data;
+ALTER TABLE execution_blocks DROP COLUMN base_fee_per_gas;
+ALTER TABLE execution_blocks DROP COLUMN gas_used;
+ALTER TABLE execution_blocks DROP COLUMN gas_limit;
+ALTER TABLE execution_blocks DROP COLUMN fee_recipient;
+ALTER TABLE execution_blocks DROP COLUMN "timestamp";
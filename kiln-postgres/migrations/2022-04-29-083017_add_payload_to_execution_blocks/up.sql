@@ -0,0 +1,12 @@
+-- Your SQL goes here
+
+ALTER TABLE execution_blocks ADD COLUMN "timestamp" BIGINT;
+ALTER TABLE execution_blocks ADD COLUMN fee_recipient BYTEA;
+ALTER TABLE execution_blocks ADD COLUMN gas_limit BIGINT;
+ALTER TABLE execution_blocks ADD COLUMN gas_used BIGINT;
+ALTER TABLE execution_blocks ADD COLUMN base_fee_per_gas BYTEA;
+ALTER TABLE execution_blocks ADD COLUMN extra_data BYTEA;
+ALTER TABLE execution_blocks ADD COLUMN logs_bloom BYTEA;
+ALTER TABLE execution_blocks ADD COLUMN prev_randao BYTEA;
+
+CREATE INDEX execution_blocks_fee_recipient_idx ON execution_blocks (fee_recipient);
@@ -0,0 +1,9 @@
+-- This file should undo anything in `up.sql`
+
+ALTER TABLE transactions DROP COLUMN access_list;
+ALTER TABLE transactions DROP COLUMN transaction_type;
+ALTER TABLE transactions DROP COLUMN max_priority_fee_per_gas;
+ALTER TABLE transactions DROP COLUMN max_fee_per_gas;
+ALTER TABLE transactions DROP COLUMN gas_price;
+ALTER TABLE transactions DROP COLUMN gas;
+ALTER TABLE transactions DROP COLUMN nonce;
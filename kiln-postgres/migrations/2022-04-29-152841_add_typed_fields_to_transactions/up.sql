@@ -0,0 +1,9 @@
+-- Your SQL goes here
+
+ALTER TABLE transactions ADD COLUMN nonce BIGINT;
+ALTER TABLE transactions ADD COLUMN gas BIGINT;
+ALTER TABLE transactions ADD COLUMN gas_price BYTEA;
+ALTER TABLE transactions ADD COLUMN max_fee_per_gas BYTEA;
+ALTER TABLE transactions ADD COLUMN max_priority_fee_per_gas BYTEA;
+ALTER TABLE transactions ADD COLUMN transaction_type INTEGER;
+ALTER TABLE transactions ADD COLUMN access_list JSONB;
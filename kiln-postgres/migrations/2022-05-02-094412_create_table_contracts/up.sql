@@ -0,0 +1,16 @@
+-- Your SQL goes here
+
+CREATE TABLE contracts (
+    "address" BYTEA NOT NULL,
+    creator BYTEA NOT NULL,
+    creation_transaction BYTEA NOT NULL REFERENCES transactions(hash),
+    block_number BIGINT NOT NULL,
+    code_hash BYTEA NOT NULL,
+    bytecode BYTEA NOT NULL,
+    chain VARCHAR NOT NULL,
+    PRIMARY KEY (chain, "address")
+);
+
+CREATE INDEX contracts_creator_idx ON contracts (creator);
+CREATE INDEX contracts_creation_transaction_idx ON contracts (creation_transaction);
+CREATE INDEX contracts_code_hash_idx ON contracts (code_hash);
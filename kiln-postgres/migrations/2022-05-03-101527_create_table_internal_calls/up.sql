@@ -0,0 +1,18 @@
+-- Your SQL goes here
+
+-- Calls made by contracts during the execution of a transaction, in execution order
+CREATE TABLE internal_calls (
+    transaction_hash BYTEA NOT NULL REFERENCES transactions(hash),
+    position BIGINT NOT NULL,
+    depth INTEGER NOT NULL,
+    call_type VARCHAR NOT NULL,
+    "from" BYTEA NOT NULL,
+    "to" BYTEA,
+    "value" BYTEA NOT NULL,
+    error VARCHAR,
+    chain VARCHAR NOT NULL,
+    PRIMARY KEY (transaction_hash, position)
+);
+
+CREATE INDEX internal_calls_from_idx ON internal_calls ("from");
+CREATE INDEX internal_calls_to_idx ON internal_calls ("to");
@@ -0,0 +1,21 @@
+-- Your SQL goes here
+
+-- Transfers of ERC-20, ERC-721 and ERC-1155 tokens, decoded from the logs
+CREATE TABLE token_transfers (
+    transaction_hash BYTEA NOT NULL REFERENCES transactions(hash),
+    log_index BIGINT NOT NULL,
+    -- Position in the ids of an ERC-1155 TransferBatch, 0 otherwise
+    batch_index INTEGER NOT NULL,
+    token BYTEA NOT NULL,
+    standard VARCHAR NOT NULL,
+    "from" BYTEA NOT NULL,
+    "to" BYTEA NOT NULL,
+    token_id BYTEA,
+    "value" BYTEA NOT NULL,
+    chain VARCHAR NOT NULL,
+    PRIMARY KEY (transaction_hash, log_index, batch_index)
+);
+
+CREATE INDEX token_transfers_from_idx ON token_transfers ("from");
+CREATE INDEX token_transfers_to_idx ON token_transfers ("to");
+CREATE INDEX token_transfers_token_idx ON token_transfers (token);
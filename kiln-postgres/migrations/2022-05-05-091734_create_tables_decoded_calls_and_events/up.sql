@@ -0,0 +1,27 @@
+-- Your SQL goes here
+
+-- Transaction inputs decoded with an ABI of the registry
+CREATE TABLE decoded_calls (
+    transaction_hash BYTEA PRIMARY KEY REFERENCES transactions(hash),
+    contract BYTEA NOT NULL,
+    function_name VARCHAR NOT NULL,
+    signature VARCHAR NOT NULL,
+    params JSONB NOT NULL,
+    chain VARCHAR NOT NULL
+);
+
+CREATE INDEX decoded_calls_contract_idx ON decoded_calls (contract);
+
+-- Logs decoded with an ABI of the registry
+CREATE TABLE decoded_events (
+    transaction_hash BYTEA NOT NULL REFERENCES transactions(hash),
+    log_index BIGINT NOT NULL,
+    contract BYTEA NOT NULL,
+    event_name VARCHAR NOT NULL,
+    signature VARCHAR NOT NULL,
+    params JSONB NOT NULL,
+    chain VARCHAR NOT NULL,
+    PRIMARY KEY (transaction_hash, log_index)
+);
+
+CREATE INDEX decoded_events_contract_idx ON decoded_events (contract);
@@ -0,0 +1,9 @@
+-- This file should undo anything in `up.sql`
+
+UPDATE slots
+SET finalized = FALSE
+WHERE finalized IS NULL;
+
+ALTER TABLE slots
+ALTER COLUMN finalized SET DEFAULT FALSE,
+ALTER COLUMN finalized SET NOT NULL;
@@ -0,0 +1,17 @@
+-- Your SQL goes here
+
+-- NULL when finality was not tracked while the slot was stored, so that enabling the tracking
+-- later only promotes the provisional slots
+ALTER TABLE slots
+ALTER COLUMN finalized DROP NOT NULL,
+ALTER COLUMN finalized DROP DEFAULT;
+
+-- Provisional slots are always above the highest finalized slot of their chain, the others were
+-- stored without tracking finality
+UPDATE slots s
+SET finalized = NULL
+WHERE NOT s.finalized
+AND s.height < COALESCE(
+    (SELECT MAX(f.height) FROM slots f WHERE f.chain = s.chain AND f.finalized),
+    (SELECT MAX(a.height) + 1 FROM slots a WHERE a.chain = s.chain)
+);
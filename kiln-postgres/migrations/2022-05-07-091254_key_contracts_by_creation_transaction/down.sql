@@ -0,0 +1,12 @@
+-- This file should undo anything in `up.sql`
+
+-- Only the first deployment at each address is kept
+DELETE FROM contracts c
+WHERE EXISTS (
+    SELECT 1 FROM contracts o
+    WHERE o.chain = c.chain AND o."address" = c."address"
+    AND (o.block_number, o.creation_transaction) < (c.block_number, c.creation_transaction)
+);
+
+ALTER TABLE contracts DROP CONSTRAINT contracts_pkey;
+ALTER TABLE contracts ADD PRIMARY KEY (chain, "address");
@@ -0,0 +1,5 @@
+-- Your SQL goes here
+
+-- A contract destroyed with SELFDESTRUCT can be deployed again at the same address with CREATE2
+ALTER TABLE contracts DROP CONSTRAINT contracts_pkey;
+ALTER TABLE contracts ADD PRIMARY KEY (chain, "address", creation_transaction);
@@ -0,0 +1,62 @@
+use diesel::{Insertable, PgConnection, QueryResult, RunQueryDsl};
+
+use crate::schema::attestation_duties;
+
+/// The committee a validator is assigned to for an epoch
+#[derive(Insertable)]
+#[table_name = "attestation_duties"]
+pub struct NewAttestationDuty {
+	validator_index: i64,
+	epoch: i64,
+	slot: i64,
+	committee_index: i64,
+	chain: String,
+}
+
+impl NewAttestationDuty {
+	/// Return a new insertable duty of a `chain` validator, expected to attest at `slot`
+	pub fn new(
+		chain: &str,
+		validator_index: u64,
+		epoch: u64,
+		slot: u64,
+		committee_index: u64,
+	) -> NewAttestationDuty {
+		NewAttestationDuty {
+			validator_index: validator_index as i64,
+			epoch: epoch as i64,
+			slot: slot as i64,
+			committee_index: committee_index as i64,
+			chain: chain.to_string(),
+		}
+	}
+}
+
+/// An wrapper around an array of attestation duties
+pub struct NewAttestationDuties(Vec<NewAttestationDuty>);
+
+impl NewAttestationDuties {
+	/// Insert an array of duties in db
+	///
+	/// On conflict do nothing
+	pub fn batch_insert(&self, conn: &PgConnection) -> QueryResult<()> {
+		for chunk in self.0.chunks(1000) {
+			diesel::insert_into(attestation_duties::table)
+				.values(chunk)
+				.on_conflict_do_nothing()
+				.execute(conn)?;
+		}
+
+		Ok(())
+	}
+}
+
+impl FromIterator<NewAttestationDuty> for NewAttestationDuties {
+	fn from_iter<T: IntoIterator<Item = NewAttestationDuty>>(iter: T) -> Self {
+		let mut duties = vec![];
+		for d in iter {
+			duties.push(d);
+		}
+		NewAttestationDuties(duties)
+	}
+}
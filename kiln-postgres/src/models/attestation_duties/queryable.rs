@@ -0,0 +1,146 @@
+use diesel::{
+	sql_types::{BigInt, Bool, Nullable, Text},
+	PgConnection, QueryResult, RunQueryDsl,
+};
+use serde::{Deserialize, Serialize};
+
+// Only the mainnet preset is supported by the indexer
+const SLOTS_PER_EPOCH: i64 = 32;
+
+// Every duty along its first inclusion, with the votes checked against the stored beacon blocks
+//
+// The head vote is correct if it matches the last block proposed up to the duty slot,
+// the target vote if it matches the last block proposed up to the start of the epoch.
+// Votes are NULL when the attestation was never included.
+const PERFORMANCE_QUERY: &str = r#"SELECT d.validator_index, d.epoch, d.slot, i.inclusion_slot,
+	i.beacon_block_root = (
+		SELECT s.block_root FROM slots s
+		WHERE s.chain = d.chain AND s.height <= d.slot AND s.block_root IS NOT NULL
+		ORDER BY s.height DESC LIMIT 1
+	) AS head_correct,
+	i.target_root = (
+		SELECT s.block_root FROM slots s
+		WHERE s.chain = d.chain AND s.height <= d.epoch * $2 AND s.block_root IS NOT NULL
+		ORDER BY s.height DESC LIMIT 1
+	) AS target_correct
+	FROM attestation_duties d
+	LEFT JOIN LATERAL (
+		SELECT inclusion_slot, beacon_block_root, target_root FROM attestation_inclusions
+		WHERE chain = d.chain AND validator_index = d.validator_index AND epoch = d.epoch
+		ORDER BY inclusion_slot ASC LIMIT 1
+	) i ON TRUE
+	WHERE d.chain = $1"#;
+
+#[derive(QueryableByName)]
+struct DbAttestationPerformance {
+	#[sql_type = "BigInt"]
+	validator_index: i64,
+	#[sql_type = "BigInt"]
+	epoch: i64,
+	#[sql_type = "BigInt"]
+	slot: i64,
+	#[sql_type = "Nullable<BigInt>"]
+	inclusion_slot: Option<i64>,
+	#[sql_type = "Nullable<Bool>"]
+	head_correct: Option<bool>,
+	#[sql_type = "Nullable<Bool>"]
+	target_correct: Option<bool>,
+}
+
+/// How well a validator fulfilled its attestation duty for an epoch
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AttestationPerformance {
+	validator_index: u64,
+	epoch: u64,
+	slot: u64,
+	inclusion_slot: Option<u64>,
+	head_correct: bool,
+	target_correct: bool,
+}
+
+impl From<DbAttestationPerformance> for AttestationPerformance {
+	fn from(db_perf: DbAttestationPerformance) -> Self {
+		AttestationPerformance {
+			validator_index: db_perf.validator_index as u64,
+			epoch: db_perf.epoch as u64,
+			slot: db_perf.slot as u64,
+			inclusion_slot: db_perf.inclusion_slot.map(|s| s as u64),
+			head_correct: db_perf.head_correct.unwrap_or(false),
+			target_correct: db_perf.target_correct.unwrap_or(false),
+		}
+	}
+}
+
+impl AttestationPerformance {
+	/// Return the index of the validator
+	pub fn validator_index(&self) -> u64 {
+		self.validator_index
+	}
+
+	/// Return the epoch of the duty
+	pub fn epoch(&self) -> u64 {
+		self.epoch
+	}
+
+	/// Return true if the attestation was never included
+	pub fn is_missed(&self) -> bool {
+		self.inclusion_slot.is_none()
+	}
+
+	/// Return the number of slots between the duty and the first inclusion of the attestation
+	pub fn inclusion_distance(&self) -> Option<u64> {
+		self.inclusion_slot.map(|s| s - self.slot)
+	}
+
+	/// Return true if the head vote was correct
+	pub fn is_head_correct(&self) -> bool {
+		self.head_correct
+	}
+
+	/// Return true if the target vote was correct
+	pub fn is_target_correct(&self) -> bool {
+		self.target_correct
+	}
+
+	/// Return the performance of a validator between `from_epoch` and `to_epoch` included,
+	/// oldest first
+	pub fn list(
+		conn: &PgConnection,
+		chain: &str,
+		validator_index: u64,
+		from_epoch: u64,
+		to_epoch: u64,
+	) -> QueryResult<Vec<AttestationPerformance>> {
+		let query = format!(
+			"{PERFORMANCE_QUERY} AND d.validator_index = $3 AND d.epoch BETWEEN $4 AND $5 \
+			 ORDER BY d.epoch ASC"
+		);
+
+		let db_perfs: Vec<DbAttestationPerformance> = diesel::sql_query(query)
+			.bind::<Text, _>(chain)
+			.bind::<BigInt, _>(SLOTS_PER_EPOCH)
+			.bind::<BigInt, _>(validator_index as i64)
+			.bind::<BigInt, _>(from_epoch as i64)
+			.bind::<BigInt, _>(to_epoch as i64)
+			.load(conn)?;
+
+		Ok(db_perfs.into_iter().map(|p| p.into()).collect())
+	}
+
+	/// Return the performance of every validator with a duty at `epoch`
+	pub fn list_epoch(
+		conn: &PgConnection,
+		chain: &str,
+		epoch: u64,
+	) -> QueryResult<Vec<AttestationPerformance>> {
+		let query = format!("{PERFORMANCE_QUERY} AND d.epoch = $3 ORDER BY d.validator_index ASC");
+
+		let db_perfs: Vec<DbAttestationPerformance> = diesel::sql_query(query)
+			.bind::<Text, _>(chain)
+			.bind::<BigInt, _>(SLOTS_PER_EPOCH)
+			.bind::<BigInt, _>(epoch as i64)
+			.load(conn)?;
+
+		Ok(db_perfs.into_iter().map(|p| p.into()).collect())
+	}
+}
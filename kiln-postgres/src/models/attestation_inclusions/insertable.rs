@@ -0,0 +1,92 @@
+use diesel::{ExpressionMethods, Insertable, PgConnection, QueryDsl, QueryResult, RunQueryDsl};
+use primitive_types::H256;
+
+use crate::{
+	models::Hash256,
+	schema::{
+		attestation_inclusions,
+		attestation_inclusions::dsl::attestation_inclusions as dsl_attestation_inclusions,
+	},
+};
+
+/// The vote of a validator, included in a beacon block
+#[derive(Insertable)]
+#[table_name = "attestation_inclusions"]
+pub struct NewAttestationInclusion {
+	validator_index: i64,
+	epoch: i64,
+	inclusion_slot: i64,
+	beacon_block_root: Hash256,
+	source_epoch: i64,
+	source_root: Hash256,
+	target_epoch: i64,
+	target_root: Hash256,
+	chain: String,
+}
+
+impl NewAttestationInclusion {
+	/// Return a new insertable inclusion of a `chain` validator vote for `epoch`, in the block
+	/// of `inclusion_slot`
+	pub fn new(
+		chain: &str,
+		validator_index: u64,
+		epoch: u64,
+		inclusion_slot: u64,
+		beacon_block_root: H256,
+		source: (u64, H256),
+		target: (u64, H256),
+	) -> NewAttestationInclusion {
+		NewAttestationInclusion {
+			validator_index: validator_index as i64,
+			epoch: epoch as i64,
+			inclusion_slot: inclusion_slot as i64,
+			beacon_block_root: beacon_block_root.into(),
+			source_epoch: source.0 as i64,
+			source_root: source.1.into(),
+			target_epoch: target.0 as i64,
+			target_root: target.1.into(),
+			chain: chain.to_string(),
+		}
+	}
+
+	/// Delete the inclusions of the block at `inclusion_slot`
+	///
+	/// Used when the block is replaced before finality
+	pub fn delete_at(conn: &PgConnection, chain: &str, inclusion_slot: u64) -> QueryResult<usize> {
+		diesel::delete(
+			dsl_attestation_inclusions
+				.filter(attestation_inclusions::chain.eq(chain))
+				.filter(attestation_inclusions::inclusion_slot.eq(inclusion_slot as i64)),
+		)
+		.execute(conn)
+	}
+}
+
+/// An wrapper around an array of attestation inclusions
+pub struct NewAttestationInclusions(Vec<NewAttestationInclusion>);
+
+impl NewAttestationInclusions {
+	/// Insert an array of inclusions in db
+	///
+	/// On conflict do nothing, a vote may be aggregated several times in the same block
+	pub fn batch_insert(&self, conn: &PgConnection) -> QueryResult<()> {
+		for chunk in self.0.chunks(1000) {
+			diesel::insert_into(attestation_inclusions::table)
+				.values(chunk)
+				.on_conflict_do_nothing()
+				.execute(conn)?;
+		}
+
+		Ok(())
+	}
+}
+
+impl FromIterator<NewAttestationInclusion> for NewAttestationInclusions {
+	fn from_iter<T: IntoIterator<Item = NewAttestationInclusion>>(iter: T) -> Self {
+		let mut inclusions = vec![];
+		for i in iter {
+			inclusions.push(i);
+		}
+		NewAttestationInclusions(inclusions)
+	}
+}
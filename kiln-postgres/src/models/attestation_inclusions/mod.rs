@@ -0,0 +1,3 @@
+mod insertable;
+
+pub use insertable::*;
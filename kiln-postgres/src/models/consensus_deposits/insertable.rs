@@ -0,0 +1,84 @@
+use diesel::{ExpressionMethods, Insertable, PgConnection, QueryDsl, QueryResult, RunQueryDsl};
+use primitive_types::H256;
+
+use crate::{
+	models::Hash256,
+	schema::{
+		consensus_deposits, consensus_deposits::dsl::consensus_deposits as dsl_consensus_deposits,
+	},
+};
+
+/// A deposit processed by the beacon chain, included in the block of `inclusion_slot`
+#[derive(Insertable)]
+#[table_name = "consensus_deposits"]
+pub struct NewConsensusDeposit {
+	deposit_index: i64,
+	inclusion_slot: i64,
+	pubkey: String,
+	withdrawal_credentials: Hash256,
+	amount: i64,
+	signature: Vec<u8>,
+	chain: String,
+}
+
+impl NewConsensusDeposit {
+	/// Return a new insertable deposit of `chain`
+	///
+	/// `amount` is in gwei
+	pub fn new(
+		chain: &str,
+		deposit_index: u64,
+		inclusion_slot: u64,
+		pubkey: String,
+		withdrawal_credentials: H256,
+		amount: u64,
+		signature: Vec<u8>,
+	) -> NewConsensusDeposit {
+		NewConsensusDeposit {
+			deposit_index: deposit_index as i64,
+			inclusion_slot: inclusion_slot as i64,
+			pubkey,
+			withdrawal_credentials: withdrawal_credentials.into(),
+			amount: amount as i64,
+			signature,
+			chain: chain.to_string(),
+		}
+	}
+
+	/// Delete the deposits included in the block at `inclusion_slot`
+	///
+	/// Used when the block is replaced before finality
+	pub fn delete_at(conn: &PgConnection, chain: &str, inclusion_slot: u64) -> QueryResult<usize> {
+		diesel::delete(
+			dsl_consensus_deposits
+				.filter(consensus_deposits::chain.eq(chain))
+				.filter(consensus_deposits::inclusion_slot.eq(inclusion_slot as i64)),
+		)
+		.execute(conn)
+	}
+}
+
+/// An wrapper around an array of consensus deposits
+pub struct NewConsensusDeposits(Vec<NewConsensusDeposit>);
+
+impl NewConsensusDeposits {
+	/// Insert an array of deposits in db
+	///
+	/// On conflict do nothing
+	pub fn batch_insert(&self, conn: &PgConnection) -> QueryResult<usize> {
+		diesel::insert_into(consensus_deposits::table)
+			.values(&self.0)
+			.on_conflict_do_nothing()
+			.execute(conn)
+	}
+}
+
+impl FromIterator<NewConsensusDeposit> for NewConsensusDeposits {
+	fn from_iter<T: IntoIterator<Item = NewConsensusDeposit>>(iter: T) -> Self {
+		let mut deposits = vec![];
+		for d in iter {
+			deposits.push(d);
+		}
+		NewConsensusDeposits(deposits)
+	}
+}
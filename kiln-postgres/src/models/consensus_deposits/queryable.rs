@@ -0,0 +1,104 @@
+use diesel::{
+	ExpressionMethods, Identifiable, PgConnection, QueryDsl, QueryResult, Queryable, RunQueryDsl,
+};
+use primitive_types::H256;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	models::Hash256,
+	schema::{
+		consensus_deposits, consensus_deposits::dsl::consensus_deposits as dsl_consensus_deposits,
+	},
+};
+
+#[derive(Queryable, Identifiable)]
+#[primary_key(chain, deposit_index)]
+#[table_name = "consensus_deposits"]
+struct DbConsensusDeposit {
+	deposit_index: i64,
+	inclusion_slot: i64,
+	pubkey: String,
+	withdrawal_credentials: Hash256,
+	amount: i64,
+	signature: Vec<u8>,
+	chain: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ConsensusDeposit {
+	deposit_index: u64,
+	inclusion_slot: u64,
+	pubkey: String,
+	withdrawal_credentials: H256,
+	amount: u64,
+	signature: Vec<u8>,
+	chain: String,
+}
+
+impl From<DbConsensusDeposit> for ConsensusDeposit {
+	fn from(db_deposit: DbConsensusDeposit) -> Self {
+		ConsensusDeposit {
+			deposit_index: db_deposit.deposit_index as u64,
+			inclusion_slot: db_deposit.inclusion_slot as u64,
+			pubkey: db_deposit.pubkey,
+			withdrawal_credentials: db_deposit.withdrawal_credentials.into(),
+			amount: db_deposit.amount as u64,
+			signature: db_deposit.signature,
+			chain: db_deposit.chain,
+		}
+	}
+}
+
+impl ConsensusDeposit {
+	/// Return the index of the deposit in the deposit contract
+	pub fn deposit_index(&self) -> u64 {
+		self.deposit_index
+	}
+
+	/// Return the slot of the block including the deposit
+	pub fn inclusion_slot(&self) -> u64 {
+		self.inclusion_slot
+	}
+
+	/// Return the public key of the validator
+	pub fn pubkey(&self) -> &str {
+		&self.pubkey
+	}
+
+	/// Return the withdrawal credentials of the validator
+	pub fn withdrawal_credentials(&self) -> H256 {
+		self.withdrawal_credentials
+	}
+
+	/// Return the deposited amount, in gwei
+	pub fn amount(&self) -> u64 {
+		self.amount
+	}
+
+	/// Return the signature of the deposit message
+	pub fn signature(&self) -> &[u8] {
+		&self.signature
+	}
+
+	/// Return all the deposits of `chain`, lowest index first
+	pub fn list(conn: &PgConnection, chain: &str) -> QueryResult<Vec<ConsensusDeposit>> {
+		let db_deposits: Vec<DbConsensusDeposit> = dsl_consensus_deposits
+			.filter(consensus_deposits::chain.eq(chain))
+			.order(consensus_deposits::deposit_index.asc())
+			.load(conn)?;
+
+		Ok(db_deposits.into_iter().map(|d| d.into()).collect())
+	}
+
+	/// Return the deposit of `chain` at `deposit_index`
+	pub fn get(
+		conn: &PgConnection,
+		chain: &str,
+		deposit_index: u64,
+	) -> QueryResult<ConsensusDeposit> {
+		let db_deposit: DbConsensusDeposit =
+			dsl_consensus_deposits.find((chain, deposit_index as i64)).first(conn)?;
+
+		Ok(db_deposit.into())
+	}
+}
@@ -0,0 +1,71 @@
+use diesel::{Insertable, PgConnection, QueryResult, RunQueryDsl};
+use primitive_types::{H160, H256};
+
+use crate::{
+	models::{Hash160, Hash256},
+	schema::contracts,
+};
+
+/// A contract deployed by `creator` during `creation_transaction`
+#[derive(Insertable)]
+#[table_name = "contracts"]
+pub struct NewContract {
+	address: Hash160,
+	creator: Hash160,
+	creation_transaction: Hash256,
+	block_number: i64,
+	code_hash: Hash256,
+	bytecode: Vec<u8>,
+	chain: String,
+}
+
+impl NewContract {
+	/// Return a new insertable contract of `chain`
+	///
+	/// `creator` is the sender of the transaction, or the contract that created this one when
+	/// deployed by a factory. `bytecode` is the runtime bytecode and `code_hash` its keccak256.
+	pub fn new(
+		chain: &str,
+		address: H160,
+		creator: H160,
+		creation_transaction: H256,
+		block_number: u64,
+		code_hash: H256,
+		bytecode: Vec<u8>,
+	) -> NewContract {
+		NewContract {
+			address: address.into(),
+			creator: creator.into(),
+			creation_transaction: creation_transaction.into(),
+			block_number: block_number as i64,
+			code_hash: code_hash.into(),
+			bytecode,
+			chain: chain.to_string(),
+		}
+	}
+}
+
+/// An wrapper around an array of contracts
+pub struct NewContracts(Vec<NewContract>);
+
+impl NewContracts {
+	/// Insert an array of contracts in db
+	///
+	/// On conflict do nothing
+	pub fn batch_insert(&self, conn: &PgConnection) -> QueryResult<usize> {
+		diesel::insert_into(contracts::table)
+			.values(&self.0)
+			.on_conflict_do_nothing()
+			.execute(conn)
+	}
+}
+
+impl FromIterator<NewContract> for NewContracts {
+	fn from_iter<T: IntoIterator<Item = NewContract>>(iter: T) -> Self {
+		let mut contracts = vec![];
+		for c in iter {
+			contracts.push(c);
+		}
+		NewContracts(contracts)
+	}
+}
@@ -0,0 +1,120 @@
+use diesel::{
+	sql_types::{Binary, Nullable, Text},
+	ExpressionMethods, Identifiable, PgConnection, QueryDsl, QueryResult, Queryable, RunQueryDsl,
+};
+use primitive_types::{H160, H256};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	models::{Hash160, Hash256},
+	schema::{contracts, contracts::dsl::contracts as dsl_contracts},
+};
+
+#[derive(Queryable, QueryableByName, Identifiable)]
+#[primary_key(chain, address, creation_transaction)]
+#[table_name = "contracts"]
+struct DbContract {
+	address: Hash160,
+	creator: Hash160,
+	creation_transaction: Hash256,
+	block_number: i64,
+	code_hash: Hash256,
+	bytecode: Vec<u8>,
+	chain: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Contract {
+	address: H160,
+	creator: H160,
+	creation_transaction: H256,
+	block_number: u64,
+	code_hash: H256,
+	bytecode: Vec<u8>,
+	chain: String,
+}
+
+impl From<DbContract> for Contract {
+	fn from(db_contract: DbContract) -> Self {
+		Contract {
+			address: db_contract.address.into(),
+			creator: db_contract.creator.into(),
+			creation_transaction: db_contract.creation_transaction.into(),
+			block_number: db_contract.block_number as u64,
+			code_hash: db_contract.code_hash.into(),
+			bytecode: db_contract.bytecode,
+			chain: db_contract.chain,
+		}
+	}
+}
+
+impl Contract {
+	/// Return the address of the contract
+	pub fn address(&self) -> H160 {
+		self.address
+	}
+
+	/// Return the address that created the contract
+	pub fn creator(&self) -> H160 {
+		self.creator
+	}
+
+	/// Return the hash of the transaction that created the contract
+	pub fn creation_transaction(&self) -> H256 {
+		self.creation_transaction
+	}
+
+	/// Return the number of the block the contract was created in
+	pub fn block_number(&self) -> u64 {
+		self.block_number
+	}
+
+	/// Return the keccak256 of the runtime bytecode
+	pub fn code_hash(&self) -> H256 {
+		self.code_hash
+	}
+
+	/// Return the runtime bytecode
+	pub fn bytecode(&self) -> &[u8] {
+		&self.bytecode
+	}
+
+	/// Return the last contract of `chain` deployed at an address
+	///
+	/// An address holds several contracts when it was destroyed and deployed again with CREATE2
+	pub fn get(conn: &PgConnection, chain: &str, address: H160) -> QueryResult<Contract> {
+		let address: Hash160 = address.into();
+
+		let db_contract: DbContract = dsl_contracts
+			.filter(contracts::chain.eq(chain))
+			.filter(contracts::address.eq(address))
+			.order(contracts::block_number.desc())
+			.first(conn)?;
+
+		Ok(db_contract.into())
+	}
+
+	/// Return the contracts deployed by `address`, oldest first
+	///
+	/// A contract is deployed by the account that sent the transaction for a top level
+	/// deployment, and by the calling contract for a deployment through a factory.
+	/// Only contracts of `chain` are considered if provided.
+	pub fn list_deployed_by(
+		conn: &PgConnection,
+		address: H160,
+		chain: Option<&str>,
+	) -> QueryResult<Vec<Contract>> {
+		let address: Hash160 = address.into();
+
+		let db_contracts: Vec<DbContract> = diesel::sql_query(
+			r#"SELECT c.* FROM contracts c
+			WHERE c.creator = $1 AND ($2::VARCHAR IS NULL OR c.chain = $2)
+			ORDER BY c.block_number ASC"#,
+		)
+		.bind::<Binary, _>(address)
+		.bind::<Nullable<Text>, _>(chain)
+		.load(conn)?;
+
+		Ok(db_contracts.into_iter().map(|c| c.into()).collect())
+	}
+}
@@ -0,0 +1,79 @@
+use diesel::{Insertable, PgConnection, QueryResult, RunQueryDsl};
+use primitive_types::{H160, H256};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	models::{Hash160, Hash256},
+	schema::decoded_calls,
+};
+
+/// A parameter of a function or an event, decoded with its ABI
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DecodedParam {
+	/// Name of the parameter in the ABI, may be empty
+	pub name: String,
+	/// Solidity type of the parameter, e.g. `uint256` or `address[]`
+	pub kind: String,
+	pub value: serde_json::Value,
+}
+
+/// The input of a transaction, decoded with the ABI of the function it called
+#[derive(Insertable)]
+#[table_name = "decoded_calls"]
+pub struct NewDecodedCall {
+	transaction_hash: Hash256,
+	contract: Hash160,
+	function_name: String,
+	signature: String,
+	params: serde_json::Value,
+	chain: String,
+}
+
+impl NewDecodedCall {
+	/// Return a new insertable decoded call of `chain`, made by `transaction_hash` to `contract`
+	///
+	/// `signature` is the canonical signature of the function, e.g. `transfer(address,uint256)`
+	pub fn new(
+		chain: &str,
+		transaction_hash: H256,
+		contract: H160,
+		function_name: String,
+		signature: String,
+		params: Vec<DecodedParam>,
+	) -> NewDecodedCall {
+		NewDecodedCall {
+			transaction_hash: transaction_hash.into(),
+			contract: contract.into(),
+			function_name,
+			signature,
+			params: serde_json::to_value(params).unwrap(),
+			chain: chain.to_string(),
+		}
+	}
+}
+
+/// An wrapper around an array of decoded calls
+pub struct NewDecodedCalls(Vec<NewDecodedCall>);
+
+impl NewDecodedCalls {
+	/// Insert an array of decoded calls in db
+	///
+	/// Fail in case of conflict
+	pub fn batch_insert(&self, conn: &PgConnection) -> QueryResult<()> {
+		for chunk in self.0.chunks(1000) {
+			diesel::insert_into(decoded_calls::table).values(chunk).execute(conn)?;
+		}
+
+		Ok(())
+	}
+}
+
+impl FromIterator<NewDecodedCall> for NewDecodedCalls {
+	fn from_iter<T: IntoIterator<Item = NewDecodedCall>>(iter: T) -> Self {
+		let mut calls = vec![];
+		for c in iter {
+			calls.push(c);
+		}
+		NewDecodedCalls(calls)
+	}
+}
@@ -0,0 +1,108 @@
+use diesel::{
+	sql_types::{Binary, Nullable, Text},
+	ExpressionMethods, Identifiable, PgConnection, QueryDsl, QueryResult, Queryable, RunQueryDsl,
+};
+use primitive_types::{H160, H256};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	models::{DecodedParam, Hash160, Hash256},
+	schema::{decoded_calls, decoded_calls::dsl::decoded_calls as dsl_decoded_calls},
+};
+
+#[derive(Queryable, QueryableByName, Identifiable)]
+#[primary_key(transaction_hash)]
+#[table_name = "decoded_calls"]
+struct DbDecodedCall {
+	transaction_hash: Hash256,
+	contract: Hash160,
+	function_name: String,
+	signature: String,
+	params: serde_json::Value,
+	chain: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DecodedCall {
+	transaction_hash: H256,
+	contract: H160,
+	function_name: String,
+	signature: String,
+	params: Vec<DecodedParam>,
+	chain: String,
+}
+
+impl From<DbDecodedCall> for DecodedCall {
+	fn from(db_call: DbDecodedCall) -> Self {
+		DecodedCall {
+			transaction_hash: db_call.transaction_hash.into(),
+			contract: db_call.contract.into(),
+			function_name: db_call.function_name,
+			signature: db_call.signature,
+			params: serde_json::from_value(db_call.params).unwrap_or_default(),
+			chain: db_call.chain,
+		}
+	}
+}
+
+impl DecodedCall {
+	/// Return the hash of the decoded transaction
+	pub fn transaction_hash(&self) -> H256 {
+		self.transaction_hash
+	}
+
+	/// Return the address of the called contract
+	pub fn contract(&self) -> H160 {
+		self.contract
+	}
+
+	/// Return the name of the called function
+	pub fn function_name(&self) -> &str {
+		&self.function_name
+	}
+
+	/// Return the canonical signature of the called function
+	pub fn signature(&self) -> &str {
+		&self.signature
+	}
+
+	/// Return the decoded arguments of the call
+	pub fn params(&self) -> &[DecodedParam] {
+		&self.params
+	}
+
+	/// Return the decoded input of a transaction
+	pub fn get(conn: &PgConnection, transaction_hash: H256) -> QueryResult<DecodedCall> {
+		let transaction_hash: Hash256 = transaction_hash.into();
+
+		let db_call: DbDecodedCall = dsl_decoded_calls
+			.filter(decoded_calls::transaction_hash.eq(transaction_hash))
+			.first(conn)?;
+
+		Ok(db_call.into())
+	}
+
+	/// Return every decoded call to `contract`, in chain order
+	///
+	/// Only calls of `chain` are considered if provided
+	pub fn list_for_contract(
+		conn: &PgConnection,
+		contract: H160,
+		chain: Option<&str>,
+	) -> QueryResult<Vec<DecodedCall>> {
+		let contract: Hash160 = contract.into();
+
+		let db_calls: Vec<DbDecodedCall> = diesel::sql_query(
+			r#"SELECT c.* FROM decoded_calls c
+			JOIN transactions t ON t.hash = c.transaction_hash
+			JOIN execution_blocks b ON b.hash = t.block_hash
+			WHERE c.contract = $1 AND ($2::VARCHAR IS NULL OR c.chain = $2)
+			ORDER BY b."number" ASC, t."index" ASC"#,
+		)
+		.bind::<Binary, _>(contract)
+		.bind::<Nullable<Text>, _>(chain)
+		.load(conn)?;
+
+		Ok(db_calls.into_iter().map(|c| c.into()).collect())
+	}
+}
@@ -0,0 +1,72 @@
+use diesel::{Insertable, PgConnection, QueryResult, RunQueryDsl};
+use primitive_types::{H160, H256};
+
+use crate::{
+	models::{DecodedParam, Hash160, Hash256},
+	schema::decoded_events,
+};
+
+/// A log decoded with the ABI of the event it was emitted for
+#[derive(Insertable)]
+#[table_name = "decoded_events"]
+pub struct NewDecodedEvent {
+	transaction_hash: Hash256,
+	log_index: i64,
+	contract: Hash160,
+	event_name: String,
+	signature: String,
+	params: serde_json::Value,
+	chain: String,
+}
+
+impl NewDecodedEvent {
+	/// Return a new insertable decoded event of `chain`, emitted by `contract`
+	///
+	/// `log_index` is the index of the log in its block. `signature` is the canonical signature of
+	/// the event, e.g. `Transfer(address,address,uint256)`
+	pub fn new(
+		chain: &str,
+		transaction_hash: H256,
+		log_index: u64,
+		contract: H160,
+		event_name: String,
+		signature: String,
+		params: Vec<DecodedParam>,
+	) -> NewDecodedEvent {
+		NewDecodedEvent {
+			transaction_hash: transaction_hash.into(),
+			log_index: log_index as i64,
+			contract: contract.into(),
+			event_name,
+			signature,
+			params: serde_json::to_value(params).unwrap(),
+			chain: chain.to_string(),
+		}
+	}
+}
+
+/// An wrapper around an array of decoded events
+pub struct NewDecodedEvents(Vec<NewDecodedEvent>);
+
+impl NewDecodedEvents {
+	/// Insert an array of decoded events in db
+	///
+	/// Fail in case of conflict
+	pub fn batch_insert(&self, conn: &PgConnection) -> QueryResult<()> {
+		for chunk in self.0.chunks(1000) {
+			diesel::insert_into(decoded_events::table).values(chunk).execute(conn)?;
+		}
+
+		Ok(())
+	}
+}
+
+impl FromIterator<NewDecodedEvent> for NewDecodedEvents {
+	fn from_iter<T: IntoIterator<Item = NewDecodedEvent>>(iter: T) -> Self {
+		let mut events = vec![];
+		for e in iter {
+			events.push(e);
+		}
+		NewDecodedEvents(events)
+	}
+}
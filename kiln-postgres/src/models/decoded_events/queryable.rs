@@ -0,0 +1,120 @@
+use diesel::{
+	sql_types::{Binary, Nullable, Text},
+	ExpressionMethods, Identifiable, PgConnection, QueryDsl, QueryResult, Queryable, RunQueryDsl,
+};
+use primitive_types::{H160, H256};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	models::{DecodedParam, Hash160, Hash256},
+	schema::{decoded_events, decoded_events::dsl::decoded_events as dsl_decoded_events},
+};
+
+#[derive(Queryable, QueryableByName, Identifiable)]
+#[primary_key(transaction_hash, log_index)]
+#[table_name = "decoded_events"]
+struct DbDecodedEvent {
+	transaction_hash: Hash256,
+	log_index: i64,
+	contract: Hash160,
+	event_name: String,
+	signature: String,
+	params: serde_json::Value,
+	chain: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DecodedEvent {
+	transaction_hash: H256,
+	log_index: u64,
+	contract: H160,
+	event_name: String,
+	signature: String,
+	params: Vec<DecodedParam>,
+	chain: String,
+}
+
+impl From<DbDecodedEvent> for DecodedEvent {
+	fn from(db_event: DbDecodedEvent) -> Self {
+		DecodedEvent {
+			transaction_hash: db_event.transaction_hash.into(),
+			log_index: db_event.log_index as u64,
+			contract: db_event.contract.into(),
+			event_name: db_event.event_name,
+			signature: db_event.signature,
+			params: serde_json::from_value(db_event.params).unwrap_or_default(),
+			chain: db_event.chain,
+		}
+	}
+}
+
+impl DecodedEvent {
+	/// Return the hash of the transaction that emitted the event
+	pub fn transaction_hash(&self) -> H256 {
+		self.transaction_hash
+	}
+
+	/// Return the index of the log in its block
+	pub fn log_index(&self) -> u64 {
+		self.log_index
+	}
+
+	/// Return the address of the contract that emitted the event
+	pub fn contract(&self) -> H160 {
+		self.contract
+	}
+
+	/// Return the name of the event
+	pub fn event_name(&self) -> &str {
+		&self.event_name
+	}
+
+	/// Return the canonical signature of the event
+	pub fn signature(&self) -> &str {
+		&self.signature
+	}
+
+	/// Return the decoded parameters of the event, indexed ones included
+	pub fn params(&self) -> &[DecodedParam] {
+		&self.params
+	}
+
+	/// Return every decoded event emitted by a transaction, in execution order
+	pub fn list_for_transaction(
+		conn: &PgConnection,
+		transaction_hash: H256,
+	) -> QueryResult<Vec<DecodedEvent>> {
+		let transaction_hash: Hash256 = transaction_hash.into();
+
+		let db_events: Vec<DbDecodedEvent> = dsl_decoded_events
+			.filter(decoded_events::transaction_hash.eq(transaction_hash))
+			.order(decoded_events::log_index.asc())
+			.load(conn)?;
+
+		Ok(db_events.into_iter().map(|e| e.into()).collect())
+	}
+
+	/// Return every decoded event emitted by `contract`, in chain order
+	///
+	/// Only events of `chain` are considered if provided
+	pub fn list_for_contract(
+		conn: &PgConnection,
+		contract: H160,
+		chain: Option<&str>,
+	) -> QueryResult<Vec<DecodedEvent>> {
+		let contract: Hash160 = contract.into();
+
+		let db_events: Vec<DbDecodedEvent> = diesel::sql_query(
+			r#"SELECT e.* FROM decoded_events e
+			JOIN transactions t ON t.hash = e.transaction_hash
+			JOIN execution_blocks b ON b.hash = t.block_hash
+			WHERE e.contract = $1 AND ($2::VARCHAR IS NULL OR e.chain = $2)
+			ORDER BY b."number" ASC, e.log_index ASC"#,
+		)
+		.bind::<Binary, _>(contract)
+		.bind::<Nullable<Text>, _>(chain)
+		.load(conn)?;
+
+		Ok(db_events.into_iter().map(|e| e.into()).collect())
+	}
+}
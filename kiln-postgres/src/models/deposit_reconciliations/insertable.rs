@@ -0,0 +1,94 @@
+use diesel::{
+	pg::upsert::excluded, ExpressionMethods, Insertable, PgConnection, QueryResult, RunQueryDsl,
+};
+use primitive_types::H256;
+
+use crate::{models::Hash256, schema::deposit_reconciliations};
+
+/// What happened on the beacon chain to a deposit made on the execution layer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReconciliationStatus {
+	/// Included in a beacon block and credited to a validator
+	Processed,
+	/// Included in a beacon block, but no validator was created for its public key
+	InvalidSignature,
+	/// Not included in any beacon block yet
+	Unprocessed,
+}
+
+impl ReconciliationStatus {
+	/// Return the name stored in db
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			ReconciliationStatus::Processed => "processed",
+			ReconciliationStatus::InvalidSignature => "invalid_signature",
+			ReconciliationStatus::Unprocessed => "unprocessed",
+		}
+	}
+}
+
+/// The beacon chain outcome of a deposit made on the execution layer
+#[derive(Insertable)]
+#[table_name = "deposit_reconciliations"]
+pub struct NewDepositReconciliation {
+	transaction_hash: Hash256,
+	deposit_index: i64,
+	status: String,
+	chain: String,
+}
+
+impl NewDepositReconciliation {
+	/// Return a new insertable reconciliation of a `chain` deposit, emitted by `transaction_hash`
+	pub fn new(
+		chain: &str,
+		deposit_index: u64,
+		transaction_hash: H256,
+		status: ReconciliationStatus,
+	) -> NewDepositReconciliation {
+		NewDepositReconciliation {
+			transaction_hash: transaction_hash.into(),
+			deposit_index: deposit_index as i64,
+			status: status.as_str().to_string(),
+			chain: chain.to_string(),
+		}
+	}
+}
+
+/// An wrapper around an array of deposit reconciliations
+pub struct NewDepositReconciliations(Vec<NewDepositReconciliation>);
+
+impl NewDepositReconciliations {
+	/// Upsert an array of reconciliations in db
+	///
+	/// # Updated fields
+	/// `transaction_hash`, `status`
+	pub fn batch_upsert(&self, conn: &PgConnection) -> QueryResult<()> {
+		for chunk in self.0.chunks(1000) {
+			diesel::insert_into(deposit_reconciliations::table)
+				.values(chunk)
+				.on_conflict((
+					deposit_reconciliations::chain,
+					deposit_reconciliations::deposit_index,
+				))
+				.do_update()
+				.set((
+					deposit_reconciliations::transaction_hash
+						.eq(excluded(deposit_reconciliations::transaction_hash)),
+					deposit_reconciliations::status.eq(excluded(deposit_reconciliations::status)),
+				))
+				.execute(conn)?;
+		}
+
+		Ok(())
+	}
+}
+
+impl FromIterator<NewDepositReconciliation> for NewDepositReconciliations {
+	fn from_iter<T: IntoIterator<Item = NewDepositReconciliation>>(iter: T) -> Self {
+		let mut reconciliations = vec![];
+		for r in iter {
+			reconciliations.push(r);
+		}
+		NewDepositReconciliations(reconciliations)
+	}
+}
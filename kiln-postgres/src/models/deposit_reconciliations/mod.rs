@@ -0,0 +1,5 @@
+mod insertable;
+mod queryable;
+
+pub use insertable::*;
+pub use queryable::*;
@@ -0,0 +1,73 @@
+use diesel::{
+	ExpressionMethods, Identifiable, PgConnection, QueryDsl, QueryResult, Queryable, RunQueryDsl,
+};
+use primitive_types::H256;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	models::{Hash256, ReconciliationStatus},
+	schema::{
+		deposit_reconciliations,
+		deposit_reconciliations::dsl::deposit_reconciliations as dsl_deposit_reconciliations,
+	},
+};
+
+#[derive(Queryable, Identifiable)]
+#[primary_key(chain, deposit_index)]
+#[table_name = "deposit_reconciliations"]
+struct DbDepositReconciliation {
+	transaction_hash: Hash256,
+	deposit_index: i64,
+	status: String,
+	chain: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DepositReconciliation {
+	transaction_hash: H256,
+	deposit_index: u64,
+	status: String,
+	chain: String,
+}
+
+impl From<DbDepositReconciliation> for DepositReconciliation {
+	fn from(db_reconciliation: DbDepositReconciliation) -> Self {
+		DepositReconciliation {
+			transaction_hash: db_reconciliation.transaction_hash.into(),
+			deposit_index: db_reconciliation.deposit_index as u64,
+			status: db_reconciliation.status,
+			chain: db_reconciliation.chain,
+		}
+	}
+}
+
+impl DepositReconciliation {
+	/// Return the hash of the transaction that emitted the deposit
+	pub fn transaction_hash(&self) -> H256 {
+		self.transaction_hash
+	}
+
+	/// Return the index of the deposit in the deposit contract
+	pub fn deposit_index(&self) -> u64 {
+		self.deposit_index
+	}
+
+	/// Return the outcome of the deposit
+	pub fn status(&self) -> &str {
+		&self.status
+	}
+
+	/// Return the reconciliations of `chain` with `status`
+	pub fn list_by_status(
+		conn: &PgConnection,
+		chain: &str,
+		status: ReconciliationStatus,
+	) -> QueryResult<Vec<DepositReconciliation>> {
+		let db_reconciliations: Vec<DbDepositReconciliation> = dsl_deposit_reconciliations
+			.filter(deposit_reconciliations::chain.eq(chain))
+			.filter(deposit_reconciliations::status.eq(status.as_str()))
+			.load(conn)?;
+
+		Ok(db_reconciliations.into_iter().map(|r| r.into()).collect())
+	}
+}
@@ -0,0 +1,56 @@
+use diesel::{
+	sql_types::{Binary, Nullable, Text},
+	Connection, PgConnection, QueryResult, RunQueryDsl,
+};
+use primitive_types::H256;
+
+use crate::models::Hash256;
+
+/// The links between validators and the deposits made to register or top them up
+pub struct NewDeposits;
+
+impl NewDeposits {
+	/// Link the execution deposits of `chain` to the validators they were made for
+	///
+	/// A deposit is linked once the validator it was made for is known. Only the deposits emitted
+	/// by `transaction_hash` are considered if provided.
+	/// Deposits already linked are left untouched and the links made before the deposits were
+	/// synced are replaced.
+	///
+	/// Return the number of new links
+	pub fn link(
+		conn: &PgConnection,
+		chain: &str,
+		transaction_hash: Option<H256>,
+	) -> QueryResult<usize> {
+		let transaction_hash: Option<Hash256> = transaction_hash.map(|h| h.into());
+
+		conn.transaction(|| {
+			let rows = diesel::sql_query(
+				r#"INSERT INTO deposits (deposit_index, validator_index, transaction_hash, amount, chain)
+				SELECT d.deposit_index, v."index", d.transaction_hash, d.amount, d.chain
+				FROM execution_deposits d
+				JOIN validators v ON v.chain = d.chain AND v.pubkey = d.pubkey
+				WHERE d.chain = $1 AND ($2::BYTEA IS NULL OR d.transaction_hash = $2)
+				ON CONFLICT DO NOTHING"#,
+			)
+			.bind::<Text, _>(chain)
+			.bind::<Nullable<Binary>, _>(transaction_hash)
+			.execute(conn)?;
+
+			// The legacy links are superseded by the deposits of their transaction
+			diesel::sql_query(
+				r#"DELETE FROM deposits l
+				WHERE l.chain = $1 AND l.deposit_index IS NULL AND EXISTS (
+					SELECT 1 FROM deposits d
+					WHERE d.chain = l.chain AND d.validator_index = l.validator_index
+					AND d.transaction_hash = l.transaction_hash AND d.deposit_index IS NOT NULL
+				)"#,
+			)
+			.bind::<Text, _>(chain)
+			.execute(conn)?;
+
+			Ok(rows)
+		})
+	}
+}
@@ -0,0 +1,99 @@
+use diesel::{
+	ExpressionMethods, Identifiable, PgConnection, QueryDsl, QueryResult, Queryable, RunQueryDsl,
+};
+use primitive_types::H256;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	models::Hash256,
+	schema::{deposits, deposits::dsl::deposits as dsl_deposits},
+};
+
+#[derive(Queryable, Identifiable)]
+#[table_name = "deposits"]
+struct DbDeposit {
+	id: i64,
+	deposit_index: Option<i64>,
+	validator_index: i64,
+	transaction_hash: Hash256,
+	amount: Option<i64>,
+	chain: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Deposit {
+	deposit_index: Option<u64>,
+	validator_index: u64,
+	transaction_hash: H256,
+	amount: Option<u64>,
+	chain: String,
+}
+
+impl From<DbDeposit> for Deposit {
+	fn from(db_deposit: DbDeposit) -> Self {
+		Deposit {
+			deposit_index: db_deposit.deposit_index.map(|i| i as u64),
+			validator_index: db_deposit.validator_index as u64,
+			transaction_hash: db_deposit.transaction_hash.into(),
+			amount: db_deposit.amount.map(|a| a as u64),
+			chain: db_deposit.chain,
+		}
+	}
+}
+
+impl Deposit {
+	/// Return the index of the deposit in the deposit contract
+	///
+	/// Links made before the deposits were synced have no index until the syncers replace them
+	pub fn deposit_index(&self) -> Option<u64> {
+		self.deposit_index
+	}
+
+	/// Return the index of the validator
+	pub fn validator_index(&self) -> u64 {
+		self.validator_index
+	}
+
+	/// Return the hash of the transaction that emitted the deposit
+	pub fn transaction_hash(&self) -> H256 {
+		self.transaction_hash
+	}
+
+	/// Return the deposited amount, in gwei, if known
+	pub fn amount(&self) -> Option<u64> {
+		self.amount
+	}
+
+	/// Return every deposit made for a validator, oldest first
+	pub fn list_for_validator(
+		conn: &PgConnection,
+		chain: &str,
+		validator_index: u64,
+	) -> QueryResult<Vec<Deposit>> {
+		let db_deposits: Vec<DbDeposit> = dsl_deposits
+			.filter(deposits::chain.eq(chain))
+			.filter(deposits::validator_index.eq(validator_index as i64))
+			.order((
+				deposits::deposit_index.is_not_null(),
+				deposits::deposit_index.asc(),
+			))
+			.load(conn)?;
+
+		Ok(db_deposits.into_iter().map(|d| d.into()).collect())
+	}
+
+	/// Return every deposit emitted by a transaction, lowest index first
+	pub fn list_for_transaction(
+		conn: &PgConnection,
+		transaction_hash: H256,
+	) -> QueryResult<Vec<Deposit>> {
+		let transaction_hash: Hash256 = transaction_hash.into();
+
+		let db_deposits: Vec<DbDeposit> = dsl_deposits
+			.filter(deposits::transaction_hash.eq(transaction_hash))
+			.order(deposits::deposit_index.asc())
+			.load(conn)?;
+
+		Ok(db_deposits.into_iter().map(|d| d.into()).collect())
+	}
+}
@@ -1,8 +1,11 @@
 use crate::diesel::RunQueryDsl;
 use diesel::{Insertable, PgConnection, QueryResult};
-use primitive_types::H256;
+use primitive_types::{H160, H256, U256};
 
-use crate::{models::Hash256, schema::execution_blocks};
+use crate::{
+	models::{u256_to_vec_u8, Hash160, Hash256},
+	schema::execution_blocks,
+};
 
 #[derive(Insertable)]
 #[table_name = "execution_blocks"]
@@ -13,10 +16,20 @@ pub struct NewExecBlock {
 	state_root: Hash256,
 	transactions_root: Hash256,
 	receipts_root: Hash256,
+	chain: String,
+	timestamp: Option<i64>,
+	fee_recipient: Option<Hash160>,
+	gas_limit: Option<i64>,
+	gas_used: Option<i64>,
+	base_fee_per_gas: Option<Vec<u8>>,
+	extra_data: Option<Vec<u8>>,
+	logs_bloom: Option<Vec<u8>>,
+	prev_randao: Option<Hash256>,
 }
 
 impl NewExecBlock {
 	pub fn new(
+		chain: &str,
 		hash: H256,
 		number: u64,
 		parent_hash: H256,
@@ -31,9 +44,44 @@ impl NewExecBlock {
 			state_root: state_root.into(),
 			transactions_root: transactions_root.into(),
 			receipts_root: receipts_root.into(),
+			chain: chain.to_string(),
+			timestamp: None,
+			fee_recipient: None,
+			gas_limit: None,
+			gas_used: None,
+			base_fee_per_gas: None,
+			extra_data: None,
+			logs_bloom: None,
+			prev_randao: None,
 		}
 	}
 
+	/// Add the execution payload fields of the block
+	///
+	/// `base_fee_per_gas` is None before London, `prev_randao` is the mix hash before the Merge
+	#[allow(clippy::too_many_arguments)]
+	pub fn with_payload(
+		mut self,
+		timestamp: u64,
+		fee_recipient: H160,
+		gas_limit: u64,
+		gas_used: u64,
+		base_fee_per_gas: Option<U256>,
+		extra_data: Vec<u8>,
+		logs_bloom: Option<Vec<u8>>,
+		prev_randao: Option<H256>,
+	) -> NewExecBlock {
+		self.timestamp = Some(timestamp as i64);
+		self.fee_recipient = Some(fee_recipient.into());
+		self.gas_limit = Some(gas_limit as i64);
+		self.gas_used = Some(gas_used as i64);
+		self.base_fee_per_gas = base_fee_per_gas.map(u256_to_vec_u8);
+		self.extra_data = Some(extra_data);
+		self.logs_bloom = logs_bloom;
+		self.prev_randao = prev_randao.map(|r| r.into());
+		self
+	}
+
 	/// Upser a slot on db
 	///
 	/// On conflict do nothing
@@ -1,14 +1,35 @@
+use std::ops::RangeInclusive;
+
 use diesel::{
-	ExpressionMethods, Identifiable, PgConnection, QueryDsl, QueryResult, Queryable, RunQueryDsl,
+	Connection, ExpressionMethods, Identifiable, PgConnection, QueryDsl, QueryResult, Queryable,
+	RunQueryDsl,
 };
-use primitive_types::H256;
+use primitive_types::{H160, H256, U256};
 use serde::{Deserialize, Serialize};
 
 use crate::{
-	models::Hash256,
+	models::{list_missing_ranges, Hash160, Hash256},
 	schema::{
+		contracts,
+		contracts::dsl::contracts as dsl_contracts,
+		decoded_calls,
+		decoded_calls::dsl::decoded_calls as dsl_decoded_calls,
+		decoded_events,
+		decoded_events::dsl::decoded_events as dsl_decoded_events,
+		deposits,
+		deposits::dsl::deposits as dsl_deposits,
 		execution_blocks,
 		execution_blocks::{dsl::execution_blocks as dsl_blocks, number},
+		execution_deposits,
+		execution_deposits::dsl::execution_deposits as dsl_execution_deposits,
+		internal_calls,
+		internal_calls::dsl::internal_calls as dsl_internal_calls,
+		logs,
+		logs::dsl::logs as dsl_logs,
+		token_transfers,
+		token_transfers::dsl::token_transfers as dsl_token_transfers,
+		transactions,
+		transactions::dsl::transactions as dsl_transactions,
 	},
 };
 
@@ -22,6 +43,15 @@ struct DbExecBlock {
 	state_root: Hash256,
 	transactions_root: Hash256,
 	receipts_root: Hash256,
+	chain: String,
+	timestamp: Option<i64>,
+	fee_recipient: Option<Hash160>,
+	gas_limit: Option<i64>,
+	gas_used: Option<i64>,
+	base_fee_per_gas: Option<Vec<u8>>,
+	extra_data: Option<Vec<u8>>,
+	logs_bloom: Option<Vec<u8>>,
+	prev_randao: Option<Hash256>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -32,6 +62,15 @@ pub struct ExecBlock {
 	state_root: H256,
 	transactions_root: H256,
 	receipts_root: H256,
+	chain: String,
+	timestamp: Option<u64>,
+	fee_recipient: Option<H160>,
+	gas_limit: Option<u64>,
+	gas_used: Option<u64>,
+	base_fee_per_gas: Option<U256>,
+	extra_data: Option<Vec<u8>>,
+	logs_bloom: Option<Vec<u8>>,
+	prev_randao: Option<H256>,
 }
 
 impl From<DbExecBlock> for ExecBlock {
@@ -43,26 +82,199 @@ impl From<DbExecBlock> for ExecBlock {
 			state_root: db_block.state_root.into(),
 			transactions_root: db_block.transactions_root.into(),
 			receipts_root: db_block.receipts_root.into(),
+			chain: db_block.chain,
+			timestamp: db_block.timestamp.map(|t| t as u64),
+			fee_recipient: db_block.fee_recipient.map(|f| f.into()),
+			gas_limit: db_block.gas_limit.map(|g| g as u64),
+			gas_used: db_block.gas_used.map(|g| g as u64),
+			base_fee_per_gas: db_block.base_fee_per_gas.map(|b| U256::from_little_endian(&b)),
+			extra_data: db_block.extra_data,
+			logs_bloom: db_block.logs_bloom,
+			prev_randao: db_block.prev_randao.map(|r| r.into()),
 		}
 	}
 }
 
 impl ExecBlock {
-	/// Return the highest block from db
-	pub fn get_highest(conn: &PgConnection) -> QueryResult<ExecBlock> {
-		let block = dsl_blocks.order(execution_blocks::number.desc()).first::<DbExecBlock>(conn)?;
+	/// Return the highest block of `chain` from db
+	pub fn get_highest(conn: &PgConnection, chain: &str) -> QueryResult<ExecBlock> {
+		let block = dsl_blocks
+			.filter(execution_blocks::chain.eq(chain))
+			.order(execution_blocks::number.desc())
+			.first::<DbExecBlock>(conn)?;
 
 		Ok(block.into())
 	}
 
 	/// Return an unique block from db
-	pub fn get(conn: &PgConnection, height: u64) -> QueryResult<ExecBlock> {
-		let block = dsl_blocks.filter(number.eq(height as i64)).first::<DbExecBlock>(conn)?;
+	pub fn get(conn: &PgConnection, chain: &str, height: u64) -> QueryResult<ExecBlock> {
+		let block = dsl_blocks
+			.filter(execution_blocks::chain.eq(chain))
+			.filter(number.eq(height as i64))
+			.first::<DbExecBlock>(conn)?;
+
+		Ok(block.into())
+	}
+
+	/// Return the highest block of `chain` from db with a number lower or equal to `height`
+	pub fn get_highest_up_to(
+		conn: &PgConnection,
+		chain: &str,
+		height: u64,
+	) -> QueryResult<ExecBlock> {
+		let block = dsl_blocks
+			.filter(execution_blocks::chain.eq(chain))
+			.filter(number.le(height as i64))
+			.order(number.desc())
+			.first::<DbExecBlock>(conn)?;
 
 		Ok(block.into())
 	}
 
+	/// Delete every block of `chain` with a number greater or equal to `height`
+	///
+	/// Used to roll back the blocks orphaned by a reorg. Their transactions are deleted as well,
+	/// along their logs, their decoded inputs and events, the token transfers and internal calls
+	/// they made, the contracts they created, the deposits they emitted and the links between these
+	/// deposits and validators.
+	///
+	/// Return the number of deleted blocks
+	pub fn delete_from(conn: &PgConnection, chain: &str, height: u64) -> QueryResult<usize> {
+		let height = height as i64;
+
+		conn.transaction(|| {
+			let orphaned_blocks = || {
+				dsl_blocks
+					.filter(execution_blocks::chain.eq(chain))
+					.filter(number.ge(height))
+					.select(execution_blocks::hash)
+			};
+			let orphaned_transactions = || {
+				dsl_transactions
+					.filter(transactions::block_hash.eq_any(orphaned_blocks()))
+					.select(transactions::hash)
+			};
+
+			diesel::delete(
+				dsl_deposits.filter(deposits::transaction_hash.eq_any(orphaned_transactions())),
+			)
+			.execute(conn)?;
+
+			diesel::delete(
+				dsl_execution_deposits
+					.filter(execution_deposits::transaction_hash.eq_any(orphaned_transactions())),
+			)
+			.execute(conn)?;
+
+			diesel::delete(
+				dsl_contracts
+					.filter(contracts::creation_transaction.eq_any(orphaned_transactions())),
+			)
+			.execute(conn)?;
+
+			diesel::delete(
+				dsl_internal_calls
+					.filter(internal_calls::transaction_hash.eq_any(orphaned_transactions())),
+			)
+			.execute(conn)?;
+
+			diesel::delete(
+				dsl_decoded_calls
+					.filter(decoded_calls::transaction_hash.eq_any(orphaned_transactions())),
+			)
+			.execute(conn)?;
+
+			diesel::delete(
+				dsl_decoded_events
+					.filter(decoded_events::transaction_hash.eq_any(orphaned_transactions())),
+			)
+			.execute(conn)?;
+
+			diesel::delete(
+				dsl_token_transfers
+					.filter(token_transfers::transaction_hash.eq_any(orphaned_transactions())),
+			)
+			.execute(conn)?;
+
+			diesel::delete(dsl_logs.filter(logs::transaction_hash.eq_any(orphaned_transactions())))
+				.execute(conn)?;
+
+			diesel::delete(
+				dsl_transactions.filter(transactions::block_hash.eq_any(orphaned_blocks())),
+			)
+			.execute(conn)?;
+
+			diesel::delete(
+				dsl_blocks.filter(execution_blocks::chain.eq(chain)).filter(number.ge(height)),
+			)
+			.execute(conn)
+		})
+	}
+
+	/// Return the ranges of block numbers of `chain` that were never synced
+	pub fn list_missing_ranges(
+		conn: &PgConnection,
+		chain: &str,
+	) -> QueryResult<Vec<RangeInclusive<u64>>> {
+		list_missing_ranges(conn, "execution_blocks", "number", chain)
+	}
+
+	/// Return the name of the chain the block belongs to
+	pub fn chain(&self) -> &str {
+		&self.chain
+	}
+
+	/// Return the hash of the block
+	pub fn hash(&self) -> H256 {
+		self.hash
+	}
+
 	pub fn number(&self) -> u64 {
 		self.number
 	}
+
+	/// Return the hash of the parent block
+	pub fn parent_hash(&self) -> H256 {
+		self.parent_hash
+	}
+
+	/// Return the unix timestamp of the block, in seconds
+	pub fn timestamp(&self) -> Option<u64> {
+		self.timestamp
+	}
+
+	/// Return the address receiving the priority fees of the block
+	pub fn fee_recipient(&self) -> Option<H160> {
+		self.fee_recipient
+	}
+
+	/// Return the maximum amount of gas the block could use
+	pub fn gas_limit(&self) -> Option<u64> {
+		self.gas_limit
+	}
+
+	/// Return the amount of gas used by the transactions of the block
+	pub fn gas_used(&self) -> Option<u64> {
+		self.gas_used
+	}
+
+	/// Return the base fee per gas burnt by every transaction of the block, in wei
+	pub fn base_fee_per_gas(&self) -> Option<U256> {
+		self.base_fee_per_gas
+	}
+
+	/// Return the arbitrary data set by the block producer
+	pub fn extra_data(&self) -> Option<&[u8]> {
+		self.extra_data.as_deref()
+	}
+
+	/// Return the bloom filter of the logs emitted in the block
+	pub fn logs_bloom(&self) -> Option<&[u8]> {
+		self.logs_bloom.as_deref()
+	}
+
+	/// Return the randomness of the beacon chain the block was built on
+	pub fn prev_randao(&self) -> Option<H256> {
+		self.prev_randao
+	}
 }
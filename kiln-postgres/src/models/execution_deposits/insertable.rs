@@ -0,0 +1,71 @@
+use diesel::{Insertable, PgConnection, QueryResult, RunQueryDsl};
+use primitive_types::H256;
+
+use crate::{models::Hash256, schema::execution_deposits};
+
+/// A DepositEvent emitted by the deposit contract during `transaction_hash`
+#[derive(Insertable)]
+#[table_name = "execution_deposits"]
+pub struct NewExecutionDeposit {
+	deposit_index: i64,
+	transaction_hash: Hash256,
+	log_index: i64,
+	pubkey: String,
+	withdrawal_credentials: Hash256,
+	amount: i64,
+	signature: Vec<u8>,
+	chain: String,
+}
+
+impl NewExecutionDeposit {
+	/// Return a new insertable deposit of `chain`
+	///
+	/// `amount` is in gwei
+	#[allow(clippy::too_many_arguments)]
+	pub fn new(
+		chain: &str,
+		deposit_index: u64,
+		transaction_hash: H256,
+		log_index: u64,
+		pubkey: String,
+		withdrawal_credentials: H256,
+		amount: u64,
+		signature: Vec<u8>,
+	) -> NewExecutionDeposit {
+		NewExecutionDeposit {
+			deposit_index: deposit_index as i64,
+			transaction_hash: transaction_hash.into(),
+			log_index: log_index as i64,
+			pubkey,
+			withdrawal_credentials: withdrawal_credentials.into(),
+			amount: amount as i64,
+			signature,
+			chain: chain.to_string(),
+		}
+	}
+}
+
+/// An wrapper around an array of execution deposits
+pub struct NewExecutionDeposits(Vec<NewExecutionDeposit>);
+
+impl NewExecutionDeposits {
+	/// Insert an array of deposits in db
+	///
+	/// On conflict do nothing
+	pub fn batch_insert(&self, conn: &PgConnection) -> QueryResult<usize> {
+		diesel::insert_into(execution_deposits::table)
+			.values(&self.0)
+			.on_conflict_do_nothing()
+			.execute(conn)
+	}
+}
+
+impl FromIterator<NewExecutionDeposit> for NewExecutionDeposits {
+	fn from_iter<T: IntoIterator<Item = NewExecutionDeposit>>(iter: T) -> Self {
+		let mut deposits = vec![];
+		for d in iter {
+			deposits.push(d);
+		}
+		NewExecutionDeposits(deposits)
+	}
+}
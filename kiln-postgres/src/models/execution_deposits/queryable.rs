@@ -0,0 +1,131 @@
+use diesel::{
+	ExpressionMethods, Identifiable, PgConnection, QueryDsl, QueryResult, Queryable, RunQueryDsl,
+};
+use primitive_types::H256;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	models::Hash256,
+	schema::{
+		execution_deposits, execution_deposits::dsl::execution_deposits as dsl_execution_deposits,
+	},
+};
+
+#[derive(Queryable, Identifiable)]
+#[primary_key(chain, deposit_index)]
+#[table_name = "execution_deposits"]
+struct DbExecutionDeposit {
+	deposit_index: i64,
+	transaction_hash: Hash256,
+	log_index: i64,
+	pubkey: String,
+	withdrawal_credentials: Hash256,
+	amount: i64,
+	signature: Vec<u8>,
+	chain: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ExecutionDeposit {
+	deposit_index: u64,
+	transaction_hash: H256,
+	log_index: u64,
+	pubkey: String,
+	withdrawal_credentials: H256,
+	amount: u64,
+	signature: Vec<u8>,
+	chain: String,
+}
+
+impl From<DbExecutionDeposit> for ExecutionDeposit {
+	fn from(db_deposit: DbExecutionDeposit) -> Self {
+		ExecutionDeposit {
+			deposit_index: db_deposit.deposit_index as u64,
+			transaction_hash: db_deposit.transaction_hash.into(),
+			log_index: db_deposit.log_index as u64,
+			pubkey: db_deposit.pubkey,
+			withdrawal_credentials: db_deposit.withdrawal_credentials.into(),
+			amount: db_deposit.amount as u64,
+			signature: db_deposit.signature,
+			chain: db_deposit.chain,
+		}
+	}
+}
+
+impl ExecutionDeposit {
+	/// Return the index of the deposit in the deposit contract
+	pub fn deposit_index(&self) -> u64 {
+		self.deposit_index
+	}
+
+	/// Return the hash of the transaction that emitted the deposit
+	pub fn transaction_hash(&self) -> H256 {
+		self.transaction_hash
+	}
+
+	/// Return the index of the DepositEvent log in its block
+	pub fn log_index(&self) -> u64 {
+		self.log_index
+	}
+
+	/// Return the public key of the validator
+	pub fn pubkey(&self) -> &str {
+		&self.pubkey
+	}
+
+	/// Return the withdrawal credentials of the validator
+	pub fn withdrawal_credentials(&self) -> H256 {
+		self.withdrawal_credentials
+	}
+
+	/// Return the deposited amount, in gwei
+	pub fn amount(&self) -> u64 {
+		self.amount
+	}
+
+	/// Return the signature of the deposit message
+	pub fn signature(&self) -> &[u8] {
+		&self.signature
+	}
+
+	/// Return all the deposits of `chain`, lowest index first
+	pub fn list(conn: &PgConnection, chain: &str) -> QueryResult<Vec<ExecutionDeposit>> {
+		let db_deposits: Vec<DbExecutionDeposit> = dsl_execution_deposits
+			.filter(execution_deposits::chain.eq(chain))
+			.order(execution_deposits::deposit_index.asc())
+			.load(conn)?;
+
+		Ok(db_deposits.into_iter().map(|d| d.into()).collect())
+	}
+
+	/// Return the deposits of `chain` made for the validator `pubkey`, lowest index first
+	pub fn list_for_pubkey(
+		conn: &PgConnection,
+		chain: &str,
+		pubkey: &str,
+	) -> QueryResult<Vec<ExecutionDeposit>> {
+		let db_deposits: Vec<DbExecutionDeposit> = dsl_execution_deposits
+			.filter(execution_deposits::chain.eq(chain))
+			.filter(execution_deposits::pubkey.eq(pubkey))
+			.order(execution_deposits::deposit_index.asc())
+			.load(conn)?;
+
+		Ok(db_deposits.into_iter().map(|d| d.into()).collect())
+	}
+
+	/// Return the deposits of `chain` from `from_index` to `to_index` included, lowest index first
+	pub fn list_range(
+		conn: &PgConnection,
+		chain: &str,
+		from_index: u64,
+		to_index: u64,
+	) -> QueryResult<Vec<ExecutionDeposit>> {
+		let db_deposits: Vec<DbExecutionDeposit> = dsl_execution_deposits
+			.filter(execution_deposits::chain.eq(chain))
+			.filter(execution_deposits::deposit_index.between(from_index as i64, to_index as i64))
+			.order(execution_deposits::deposit_index.asc())
+			.load(conn)?;
+
+		Ok(db_deposits.into_iter().map(|d| d.into()).collect())
+	}
+}
@@ -0,0 +1,56 @@
+use diesel::{
+	pg::upsert::excluded, ExpressionMethods, Insertable, PgConnection, QueryResult, RunQueryDsl,
+};
+
+use crate::schema::failed_heights;
+
+/// A height a syncer failed to store, to be retried later
+#[derive(Insertable)]
+#[table_name = "failed_heights"]
+pub struct NewFailedHeight {
+	syncer: String,
+	height: i64,
+	attempts: i32,
+	error: String,
+	chain: String,
+}
+
+impl NewFailedHeight {
+	/// Return a new insertable failed height of `chain`
+	pub fn new(
+		chain: &str,
+		syncer: String,
+		height: u64,
+		attempts: u32,
+		error: String,
+	) -> NewFailedHeight {
+		NewFailedHeight {
+			syncer,
+			height: height as i64,
+			attempts: attempts as i32,
+			error,
+			chain: chain.to_string(),
+		}
+	}
+
+	/// Upsert a failed height on db
+	///
+	/// # Updated fields
+	/// `attempts` is incremented, `error` is replaced
+	pub fn upsert(&self, conn: &PgConnection) -> QueryResult<usize> {
+		diesel::insert_into(failed_heights::table)
+			.values(self)
+			.on_conflict((
+				failed_heights::chain,
+				failed_heights::syncer,
+				failed_heights::height,
+			))
+			.do_update()
+			.set((
+				failed_heights::attempts
+					.eq(failed_heights::attempts + excluded(failed_heights::attempts)),
+				failed_heights::error.eq(excluded(failed_heights::error)),
+			))
+			.execute(conn)
+	}
+}
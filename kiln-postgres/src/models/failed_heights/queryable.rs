@@ -0,0 +1,77 @@
+use diesel::{
+	ExpressionMethods, Identifiable, PgConnection, QueryDsl, QueryResult, Queryable, RunQueryDsl,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::schema::{failed_heights, failed_heights::dsl::failed_heights as dsl_failed_heights};
+
+#[derive(Queryable, Identifiable)]
+#[primary_key(chain, syncer, height)]
+#[table_name = "failed_heights"]
+struct DbFailedHeight {
+	syncer: String,
+	height: i64,
+	attempts: i32,
+	error: String,
+	chain: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct FailedHeight {
+	syncer: String,
+	height: u64,
+	attempts: u32,
+	error: String,
+	chain: String,
+}
+
+impl From<DbFailedHeight> for FailedHeight {
+	fn from(db_failed: DbFailedHeight) -> Self {
+		FailedHeight {
+			syncer: db_failed.syncer,
+			height: db_failed.height as u64,
+			attempts: db_failed.attempts as u32,
+			error: db_failed.error,
+			chain: db_failed.chain,
+		}
+	}
+}
+
+impl FailedHeight {
+	/// Return the failed height
+	pub fn height(&self) -> u64 {
+		self.height
+	}
+
+	/// Return the number of failed attempts
+	pub fn attempts(&self) -> u32 {
+		self.attempts
+	}
+
+	/// Return all the heights of `chain` that `syncer` failed to store, lowest first
+	pub fn list(conn: &PgConnection, chain: &str, syncer: &str) -> QueryResult<Vec<FailedHeight>> {
+		let db_failed: Vec<DbFailedHeight> = dsl_failed_heights
+			.filter(failed_heights::chain.eq(chain))
+			.filter(failed_heights::syncer.eq(syncer))
+			.order(failed_heights::height.asc())
+			.load(conn)?;
+
+		Ok(db_failed.into_iter().map(|f| f.into()).collect())
+	}
+
+	/// Delete a failed height once it has been stored
+	pub fn delete(
+		conn: &PgConnection,
+		chain: &str,
+		syncer: &str,
+		height: u64,
+	) -> QueryResult<usize> {
+		diesel::delete(
+			dsl_failed_heights
+				.filter(failed_heights::chain.eq(chain))
+				.filter(failed_heights::syncer.eq(syncer))
+				.filter(failed_heights::height.eq(height as i64)),
+		)
+		.execute(conn)
+	}
+}
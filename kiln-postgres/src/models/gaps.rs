@@ -0,0 +1,39 @@
+use std::ops::RangeInclusive;
+
+use diesel::{
+	sql_types::{BigInt, Text},
+	PgConnection, QueryResult, RunQueryDsl,
+};
+
+#[derive(QueryableByName)]
+struct DbGap {
+	#[sql_type = "BigInt"]
+	gap_start: i64,
+	#[sql_type = "BigInt"]
+	gap_end: i64,
+}
+
+// Return the ranges of heights missing in `column` of `table` for `chain`, between its lowest and
+// highest value
+//
+// `table` and `column` are interpolated in the query and must never come from user input
+pub(super) fn list_missing_ranges(
+	conn: &PgConnection,
+	table: &'static str,
+	column: &'static str,
+	chain: &str,
+) -> QueryResult<Vec<RangeInclusive<u64>>> {
+	let query = format!(
+		r#"SELECT "{column}" + 1 AS gap_start, "next" - 1 AS gap_end
+		FROM (
+			SELECT "{column}", LEAD("{column}") OVER (ORDER BY "{column}") AS "next"
+			FROM {table}
+			WHERE chain = $1
+		) t
+		WHERE "next" > "{column}" + 1"#
+	);
+
+	let gaps: Vec<DbGap> = diesel::sql_query(query).bind::<Text, _>(chain).load(conn)?;
+
+	Ok(gaps.into_iter().map(|g| g.gap_start as u64..=g.gap_end as u64).collect())
+}
@@ -0,0 +1,80 @@
+use diesel::{Insertable, PgConnection, QueryResult, RunQueryDsl};
+use primitive_types::{H160, H256, U256};
+
+use crate::{
+	models::{u256_to_vec_u8, Hash160, Hash256},
+	schema::internal_calls,
+};
+
+/// A call made by a contract during the execution of `transaction_hash`
+#[derive(Insertable)]
+#[table_name = "internal_calls"]
+pub struct NewInternalCall {
+	transaction_hash: Hash256,
+	position: i64,
+	depth: i32,
+	call_type: String,
+	from: Hash160,
+	to: Option<Hash160>,
+	value: Vec<u8>,
+	error: Option<String>,
+	chain: String,
+}
+
+impl NewInternalCall {
+	/// Return a new insertable internal call of `chain`
+	///
+	/// `position` is the order of the call in the transaction execution, `depth` is 1 for the
+	/// calls made by the contract the transaction was sent to.
+	/// `call_type` is the opcode, e.g. `CALL`, `DELEGATECALL` or `CREATE2`
+	#[allow(clippy::too_many_arguments)]
+	pub fn new(
+		chain: &str,
+		transaction_hash: H256,
+		position: u64,
+		depth: u32,
+		call_type: String,
+		from: H160,
+		to: Option<H160>,
+		value: U256,
+		error: Option<String>,
+	) -> NewInternalCall {
+		NewInternalCall {
+			transaction_hash: transaction_hash.into(),
+			position: position as i64,
+			depth: depth as i32,
+			call_type,
+			from: from.into(),
+			to: to.map(|t| t.into()),
+			value: u256_to_vec_u8(value),
+			error,
+			chain: chain.to_string(),
+		}
+	}
+}
+
+/// An wrapper around an array of internal calls
+pub struct NewInternalCalls(Vec<NewInternalCall>);
+
+impl NewInternalCalls {
+	/// Insert an array of internal calls in db
+	///
+	/// Fail in case of conflict
+	pub fn batch_insert(&self, conn: &PgConnection) -> QueryResult<()> {
+		for chunk in self.0.chunks(1000) {
+			diesel::insert_into(internal_calls::table).values(chunk).execute(conn)?;
+		}
+
+		Ok(())
+	}
+}
+
+impl FromIterator<NewInternalCall> for NewInternalCalls {
+	fn from_iter<T: IntoIterator<Item = NewInternalCall>>(iter: T) -> Self {
+		let mut calls = vec![];
+		for c in iter {
+			calls.push(c);
+		}
+		NewInternalCalls(calls)
+	}
+}
@@ -0,0 +1,137 @@
+use diesel::{
+	sql_types::{Binary, Nullable, Text},
+	ExpressionMethods, Identifiable, PgConnection, QueryDsl, QueryResult, Queryable, RunQueryDsl,
+};
+use primitive_types::{H160, H256, U256};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	models::{Hash160, Hash256},
+	schema::{internal_calls, internal_calls::dsl::internal_calls as dsl_internal_calls},
+};
+
+#[derive(Queryable, QueryableByName, Identifiable)]
+#[primary_key(transaction_hash, position)]
+#[table_name = "internal_calls"]
+struct DbInternalCall {
+	transaction_hash: Hash256,
+	position: i64,
+	depth: i32,
+	call_type: String,
+	from: Hash160,
+	to: Option<Hash160>,
+	value: Vec<u8>,
+	error: Option<String>,
+	chain: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct InternalCall {
+	transaction_hash: H256,
+	position: u64,
+	depth: u32,
+	call_type: String,
+	from: H160,
+	to: Option<H160>,
+	value: U256,
+	error: Option<String>,
+	chain: String,
+}
+
+impl From<DbInternalCall> for InternalCall {
+	fn from(db_call: DbInternalCall) -> Self {
+		InternalCall {
+			transaction_hash: db_call.transaction_hash.into(),
+			position: db_call.position as u64,
+			depth: db_call.depth as u32,
+			call_type: db_call.call_type,
+			from: db_call.from.into(),
+			to: db_call.to.map(|t| t.into()),
+			value: U256::from_little_endian(&db_call.value),
+			error: db_call.error,
+			chain: db_call.chain,
+		}
+	}
+}
+
+impl InternalCall {
+	/// Return the hash of the transaction the call was made in
+	pub fn transaction_hash(&self) -> H256 {
+		self.transaction_hash
+	}
+
+	/// Return the order of the call in the transaction execution
+	pub fn position(&self) -> u64 {
+		self.position
+	}
+
+	/// Return the depth of the call, 1 for the calls made by the contract the transaction was
+	/// sent to
+	pub fn depth(&self) -> u32 {
+		self.depth
+	}
+
+	/// Return the opcode of the call, e.g. `CALL`, `DELEGATECALL` or `CREATE2`
+	pub fn call_type(&self) -> &str {
+		&self.call_type
+	}
+
+	/// Return the address of the caller
+	pub fn from(&self) -> H160 {
+		self.from
+	}
+
+	/// Return the address of the callee, the created contract for a creation
+	pub fn to(&self) -> Option<H160> {
+		self.to
+	}
+
+	/// Return the value transfered by the call, in wei
+	pub fn value(&self) -> U256 {
+		self.value
+	}
+
+	/// Return the error that reverted the call, if any
+	pub fn error(&self) -> Option<&str> {
+		self.error.as_deref()
+	}
+
+	/// Return every internal call of a transaction, in execution order
+	pub fn list_for_transaction(
+		conn: &PgConnection,
+		transaction_hash: H256,
+	) -> QueryResult<Vec<InternalCall>> {
+		let transaction_hash: Hash256 = transaction_hash.into();
+
+		let db_calls: Vec<DbInternalCall> = dsl_internal_calls
+			.filter(internal_calls::transaction_hash.eq(transaction_hash))
+			.order(internal_calls::position.asc())
+			.load(conn)?;
+
+		Ok(db_calls.into_iter().map(|c| c.into()).collect())
+	}
+
+	/// Return every internal call made from or to `address`, in execution order
+	///
+	/// Only calls of `chain` are considered if provided
+	pub fn list_for_address(
+		conn: &PgConnection,
+		address: H160,
+		chain: Option<&str>,
+	) -> QueryResult<Vec<InternalCall>> {
+		let address: Hash160 = address.into();
+
+		let db_calls: Vec<DbInternalCall> = diesel::sql_query(
+			r#"SELECT c.* FROM internal_calls c
+			JOIN transactions t ON t.hash = c.transaction_hash
+			JOIN execution_blocks b ON b.hash = t.block_hash
+			WHERE (c."from" = $1 OR c."to" = $1) AND ($2::VARCHAR IS NULL OR c.chain = $2)
+			ORDER BY b."number" ASC, t."index" ASC, c.position ASC"#,
+		)
+		.bind::<Binary, _>(address)
+		.bind::<Nullable<Text>, _>(chain)
+		.load(conn)?;
+
+		Ok(db_calls.into_iter().map(|c| c.into()).collect())
+	}
+}
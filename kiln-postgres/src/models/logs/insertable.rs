@@ -0,0 +1,76 @@
+use diesel::{Insertable, PgConnection, QueryResult, RunQueryDsl};
+use primitive_types::{H160, H256};
+
+use crate::{
+	models::{Hash160, Hash256},
+	schema::logs,
+};
+
+/// A log emitted during the execution of a transaction
+#[derive(Insertable)]
+#[table_name = "logs"]
+pub struct NewLog {
+	transaction_hash: Hash256,
+	log_index: i64,
+	address: Hash160,
+	topic0: Option<Hash256>,
+	topic1: Option<Hash256>,
+	topic2: Option<Hash256>,
+	topic3: Option<Hash256>,
+	data: Vec<u8>,
+	chain: String,
+}
+
+impl NewLog {
+	/// Return a new insertable log of `chain`
+	///
+	/// `log_index` is the index of the log in its block. A log has at most 4 topics.
+	pub fn new(
+		chain: &str,
+		transaction_hash: H256,
+		log_index: u64,
+		address: H160,
+		topics: &[H256],
+		data: Vec<u8>,
+	) -> NewLog {
+		let topic = |i: usize| topics.get(i).map(|t| (*t).into());
+
+		NewLog {
+			transaction_hash: transaction_hash.into(),
+			log_index: log_index as i64,
+			address: address.into(),
+			topic0: topic(0),
+			topic1: topic(1),
+			topic2: topic(2),
+			topic3: topic(3),
+			data,
+			chain: chain.to_string(),
+		}
+	}
+}
+
+/// An wrapper around an array of logs
+pub struct NewLogs(Vec<NewLog>);
+
+impl NewLogs {
+	/// Insert an array of logs in db
+	///
+	/// Fail in case of conflict
+	pub fn batch_insert(&self, conn: &PgConnection) -> QueryResult<()> {
+		for chunk in self.0.chunks(1000) {
+			diesel::insert_into(logs::table).values(chunk).execute(conn)?;
+		}
+
+		Ok(())
+	}
+}
+
+impl FromIterator<NewLog> for NewLogs {
+	fn from_iter<T: IntoIterator<Item = NewLog>>(iter: T) -> Self {
+		let mut logs = vec![];
+		for l in iter {
+			logs.push(l);
+		}
+		NewLogs(logs)
+	}
+}
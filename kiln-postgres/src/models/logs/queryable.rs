@@ -0,0 +1,94 @@
+use diesel::{
+	ExpressionMethods, Identifiable, PgConnection, QueryDsl, QueryResult, Queryable, RunQueryDsl,
+};
+use primitive_types::{H160, H256};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	models::{Hash160, Hash256},
+	schema::{logs, logs::dsl::logs as dsl_logs},
+};
+
+#[derive(Queryable, Identifiable)]
+#[primary_key(transaction_hash, log_index)]
+#[table_name = "logs"]
+struct DbLog {
+	transaction_hash: Hash256,
+	log_index: i64,
+	address: Hash160,
+	topic0: Option<Hash256>,
+	topic1: Option<Hash256>,
+	topic2: Option<Hash256>,
+	topic3: Option<Hash256>,
+	data: Vec<u8>,
+	chain: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Log {
+	transaction_hash: H256,
+	log_index: u64,
+	address: H160,
+	topics: Vec<H256>,
+	data: Vec<u8>,
+	chain: String,
+}
+
+impl From<DbLog> for Log {
+	fn from(db_log: DbLog) -> Self {
+		Log {
+			transaction_hash: db_log.transaction_hash.into(),
+			log_index: db_log.log_index as u64,
+			address: db_log.address.into(),
+			// Topics are stored in order, the first missing one ends the list
+			topics: [db_log.topic0, db_log.topic1, db_log.topic2, db_log.topic3]
+				.into_iter()
+				.map_while(|t| t.map(|t| t.into()))
+				.collect(),
+			data: db_log.data,
+			chain: db_log.chain,
+		}
+	}
+}
+
+impl Log {
+	/// Return the hash of the transaction that emitted the log
+	pub fn transaction_hash(&self) -> H256 {
+		self.transaction_hash
+	}
+
+	/// Return the index of the log in its block
+	pub fn log_index(&self) -> u64 {
+		self.log_index
+	}
+
+	/// Return the address of the contract that emitted the log
+	pub fn address(&self) -> H160 {
+		self.address
+	}
+
+	/// Return the topics of the log, the event signature first for non anonymous events
+	pub fn topics(&self) -> &[H256] {
+		&self.topics
+	}
+
+	/// Return the non indexed data of the log
+	pub fn data(&self) -> &[u8] {
+		&self.data
+	}
+
+	/// Return every log emitted by a transaction, in execution order
+	pub fn list_for_transaction(
+		conn: &PgConnection,
+		transaction_hash: H256,
+	) -> QueryResult<Vec<Log>> {
+		let transaction_hash: Hash256 = transaction_hash.into();
+
+		let db_logs: Vec<DbLog> = dsl_logs
+			.filter(logs::transaction_hash.eq(transaction_hash))
+			.order(logs::log_index.asc())
+			.load(conn)?;
+
+		Ok(db_logs.into_iter().map(|l| l.into()).collect())
+	}
+}
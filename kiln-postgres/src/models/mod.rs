@@ -1,11 +1,51 @@
+mod attestation_duties;
+mod attestation_inclusions;
+mod consensus_deposits;
+mod contracts;
+mod decoded_calls;
+mod decoded_events;
+mod deposit_reconciliations;
+mod deposits;
 mod execution_blocks;
+mod execution_deposits;
+mod failed_heights;
+mod gaps;
+mod internal_calls;
+mod logs;
+mod slashings;
 mod slots;
+mod sync_committee_members;
+mod sync_participations;
+mod token_transfers;
 mod transactions;
 mod types;
+mod validator_balances;
+mod validator_status_changes;
 mod validators;
+mod voluntary_exits;
 
+pub use attestation_duties::*;
+pub use attestation_inclusions::*;
+pub use consensus_deposits::*;
+pub use contracts::*;
+pub use decoded_calls::*;
+pub use decoded_events::*;
+pub use deposit_reconciliations::*;
+pub use deposits::*;
 pub use execution_blocks::*;
+pub use execution_deposits::*;
+pub use failed_heights::*;
+pub(self) use gaps::*;
+pub use internal_calls::*;
+pub use logs::*;
+pub use slashings::*;
 pub use slots::*;
+pub use sync_committee_members::*;
+pub use sync_participations::*;
+pub use token_transfers::*;
 pub use transactions::*;
 pub(self) use types::*;
+pub use validator_balances::*;
+pub use validator_status_changes::*;
 pub use validators::*;
+pub use voluntary_exits::*;
@@ -0,0 +1,91 @@
+use diesel::{ExpressionMethods, Insertable, PgConnection, QueryDsl, QueryResult, RunQueryDsl};
+
+use crate::schema::{slashings, slashings::dsl::slashings as dsl_slashings};
+
+/// The kind of offence a validator was slashed for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlashingType {
+	/// Proposed two different blocks for the same slot
+	Proposer,
+	/// Signed two conflicting attestations
+	Attester,
+}
+
+impl SlashingType {
+	/// Return the name stored in db
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			SlashingType::Proposer => "proposer",
+			SlashingType::Attester => "attester",
+		}
+	}
+}
+
+/// Evidence of an offence, included in the beacon block of `slot`
+#[derive(Insertable)]
+#[table_name = "slashings"]
+pub struct NewSlashing {
+	validator_index: i64,
+	slot: i64,
+	slashing_type: String,
+	whistleblower_index: i64,
+	chain: String,
+}
+
+impl NewSlashing {
+	/// Return a new insertable slashing of a `chain` validator
+	///
+	/// The proposer of the block including the evidence is the whistleblower
+	pub fn new(
+		chain: &str,
+		validator_index: u64,
+		slot: u64,
+		slashing_type: SlashingType,
+		whistleblower_index: u64,
+	) -> NewSlashing {
+		NewSlashing {
+			validator_index: validator_index as i64,
+			slot: slot as i64,
+			slashing_type: slashing_type.as_str().to_string(),
+			whistleblower_index: whistleblower_index as i64,
+			chain: chain.to_string(),
+		}
+	}
+
+	/// Delete the slashings included in the block at `slot`
+	///
+	/// Used when the block is replaced before finality
+	pub fn delete_at(conn: &PgConnection, chain: &str, slot: u64) -> QueryResult<usize> {
+		diesel::delete(
+			dsl_slashings
+				.filter(slashings::chain.eq(chain))
+				.filter(slashings::slot.eq(slot as i64)),
+		)
+		.execute(conn)
+	}
+}
+
+/// An wrapper around an array of slashings
+pub struct NewSlashings(Vec<NewSlashing>);
+
+impl NewSlashings {
+	/// Insert an array of slashings in db
+	///
+	/// On conflict do nothing
+	pub fn batch_insert(&self, conn: &PgConnection) -> QueryResult<usize> {
+		diesel::insert_into(slashings::table)
+			.values(&self.0)
+			.on_conflict_do_nothing()
+			.execute(conn)
+	}
+}
+
+impl FromIterator<NewSlashing> for NewSlashings {
+	fn from_iter<T: IntoIterator<Item = NewSlashing>>(iter: T) -> Self {
+		let mut slashings = vec![];
+		for s in iter {
+			slashings.push(s);
+		}
+		NewSlashings(slashings)
+	}
+}
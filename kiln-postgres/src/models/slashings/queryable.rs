@@ -0,0 +1,106 @@
+use diesel::{
+	sql_types::{Binary, Nullable, Text},
+	ExpressionMethods, Identifiable, PgConnection, QueryDsl, QueryResult, Queryable, RunQueryDsl,
+};
+use primitive_types::H160;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	models::Hash160,
+	schema::{slashings, slashings::dsl::slashings as dsl_slashings},
+};
+
+#[derive(Queryable, QueryableByName, Identifiable)]
+#[primary_key(chain, validator_index, slot, slashing_type)]
+#[table_name = "slashings"]
+struct DbSlashing {
+	validator_index: i64,
+	slot: i64,
+	slashing_type: String,
+	whistleblower_index: i64,
+	chain: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Slashing {
+	validator_index: u64,
+	slot: u64,
+	slashing_type: String,
+	whistleblower_index: u64,
+	chain: String,
+}
+
+impl From<DbSlashing> for Slashing {
+	fn from(db_slashing: DbSlashing) -> Self {
+		Slashing {
+			validator_index: db_slashing.validator_index as u64,
+			slot: db_slashing.slot as u64,
+			slashing_type: db_slashing.slashing_type,
+			whistleblower_index: db_slashing.whistleblower_index as u64,
+			chain: db_slashing.chain,
+		}
+	}
+}
+
+impl Slashing {
+	/// Return the index of the slashed validator
+	pub fn validator_index(&self) -> u64 {
+		self.validator_index
+	}
+
+	/// Return the slot of the block including the evidence
+	pub fn slot(&self) -> u64 {
+		self.slot
+	}
+
+	/// Return the kind of offence, `proposer` or `attester`
+	pub fn slashing_type(&self) -> &str {
+		&self.slashing_type
+	}
+
+	/// Return the index of the validator that reported the offence
+	pub fn whistleblower_index(&self) -> u64 {
+		self.whistleblower_index
+	}
+
+	/// Return the slashings of a validator, oldest first
+	pub fn list_for_validator(
+		conn: &PgConnection,
+		chain: &str,
+		validator_index: u64,
+	) -> QueryResult<Vec<Slashing>> {
+		let db_slashings: Vec<DbSlashing> = dsl_slashings
+			.filter(slashings::chain.eq(chain))
+			.filter(slashings::validator_index.eq(validator_index as i64))
+			.order(slashings::slot.asc())
+			.load(conn)?;
+
+		Ok(db_slashings.into_iter().map(|s| s.into()).collect())
+	}
+
+	/// Return the slashings of the validators `address` deposited for, oldest first
+	///
+	/// Only validators of `chain` are considered if provided.
+	pub fn list_from_depositor(
+		conn: &PgConnection,
+		address: H160,
+		chain: Option<&str>,
+	) -> QueryResult<Vec<Slashing>> {
+		let address: Hash160 = address.into();
+
+		let db_slashings: Vec<DbSlashing> = diesel::sql_query(
+			r#"SELECT s.* FROM slashings s
+			WHERE EXISTS (
+				SELECT 1 FROM deposits d
+				JOIN transactions t ON t.hash = d.transaction_hash
+				WHERE d.chain = s.chain AND d.validator_index = s.validator_index AND t."from" = $1
+			) AND ($2::VARCHAR IS NULL OR s.chain = $2)
+			ORDER BY s.slot ASC"#,
+		)
+		.bind::<Binary, _>(address)
+		.bind::<Nullable<Text>, _>(chain)
+		.load(conn)?;
+
+		Ok(db_slashings.into_iter().map(|s| s.into()).collect())
+	}
+}
@@ -1,4 +1,6 @@
-use diesel::{Insertable, PgConnection, QueryResult, RunQueryDsl};
+use diesel::{
+	pg::upsert::excluded, ExpressionMethods, Insertable, PgConnection, QueryResult, RunQueryDsl,
+};
 use primitive_types::H256;
 
 use crate::{models::Hash256, schema::slots};
@@ -12,18 +14,80 @@ pub struct NewSlot {
 	height: i64,
 	block_hash: Option<Hash256>,
 	block_number: Option<i64>,
+	finalized: Option<bool>,
+	missed: bool,
+	chain: String,
+	block_root: Option<Hash256>,
+	proposer_index: Option<i64>,
+	graffiti: Option<String>,
+	randao_reveal: Option<Vec<u8>>,
 }
 
 impl NewSlot {
 	/// Return a new insertable slot
-	pub fn new(height: u64, block_hash: Option<H256>, block_number: Option<u64>) -> NewSlot {
+	///
+	/// `finalized` should only be true if the slot is behind the node's finalized checkpoint, and
+	/// None if finality is not tracked. Provisional slots are expected to be replaced once
+	/// finality passes them.
+	#[allow(clippy::too_many_arguments)]
+	pub fn new(
+		chain: &str,
+		height: u64,
+		block_root: H256,
+		block_hash: Option<H256>,
+		block_number: Option<u64>,
+		proposer_index: u64,
+		graffiti: String,
+		randao_reveal: Vec<u8>,
+		finalized: Option<bool>,
+	) -> NewSlot {
 		NewSlot {
 			height: height as i64,
 			block_hash: block_hash.map(|h| h.into()),
 			block_number: block_number.map(|n| n as i64),
+			finalized,
+			missed: false,
+			chain: chain.to_string(),
+			block_root: Some(block_root.into()),
+			proposer_index: Some(proposer_index as i64),
+			graffiti: Some(graffiti),
+			randao_reveal: Some(randao_reveal),
+		}
+	}
+
+	/// Return a new insertable slot for which no block was proposed
+	///
+	/// `proposer_index` is the validator that was expected to propose, if known
+	pub fn missed(
+		chain: &str,
+		height: u64,
+		proposer_index: Option<u64>,
+		finalized: Option<bool>,
+	) -> NewSlot {
+		NewSlot {
+			height: height as i64,
+			block_hash: None,
+			block_number: None,
+			finalized,
+			missed: true,
+			chain: chain.to_string(),
+			block_root: None,
+			proposer_index: proposer_index.map(|i| i as i64),
+			graffiti: None,
+			randao_reveal: None,
 		}
 	}
 
+	/// Return the root of the slot's beacon block
+	pub fn block_root(&self) -> Option<H256> {
+		self.block_root.map(|r| r.into())
+	}
+
+	/// Return the hash of the slot's execution block
+	pub fn block_hash(&self) -> Option<H256> {
+		self.block_hash.map(|h| h.into())
+	}
+
 	/// Upser a slot on db
 	///
 	/// On conflict do nothing
@@ -44,4 +108,27 @@ impl NewSlot {
 	pub fn insert(&self, conn: &PgConnection) -> QueryResult<usize> {
 		diesel::insert_into(slots::table).values(self).execute(conn)
 	}
+
+	/// Upsert a slot on db
+	///
+	/// # Updated fields
+	/// `block_hash`, `block_number`, `finalized`, `missed`, `block_root`, `proposer_index`,
+	/// `graffiti`, `randao_reveal`
+	pub fn upsert(&self, conn: &PgConnection) -> QueryResult<usize> {
+		diesel::insert_into(slots::table)
+			.values(self)
+			.on_conflict((slots::chain, slots::height))
+			.do_update()
+			.set((
+				slots::block_hash.eq(excluded(slots::block_hash)),
+				slots::block_number.eq(excluded(slots::block_number)),
+				slots::finalized.eq(excluded(slots::finalized)),
+				slots::missed.eq(excluded(slots::missed)),
+				slots::block_root.eq(excluded(slots::block_root)),
+				slots::proposer_index.eq(excluded(slots::proposer_index)),
+				slots::graffiti.eq(excluded(slots::graffiti)),
+				slots::randao_reveal.eq(excluded(slots::randao_reveal)),
+			))
+			.execute(conn)
+	}
 }
@@ -1,5 +1,7 @@
+use std::ops::RangeInclusive;
+
 use crate::{
-	models::Hash256,
+	models::{list_missing_ranges, Hash256},
 	schema::{slots, slots::dsl::slots as dsl_slots},
 };
 use diesel::{
@@ -9,7 +11,7 @@ use primitive_types::H256;
 use serde::{Deserialize, Serialize};
 
 #[derive(Queryable, Identifiable)]
-#[primary_key(height)]
+#[primary_key(chain, height)]
 #[table_name = "slots"]
 struct DbSlot {
 	// postgresql doesn't support unsigned types
@@ -17,6 +19,13 @@ struct DbSlot {
 	height: i64,
 	block_hash: Option<Hash256>,
 	block_number: Option<i64>,
+	finalized: Option<bool>,
+	missed: bool,
+	chain: String,
+	block_root: Option<Hash256>,
+	proposer_index: Option<i64>,
+	graffiti: Option<String>,
+	randao_reveal: Option<Vec<u8>>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -24,6 +33,13 @@ pub struct Slot {
 	height: u64,
 	block_hash: Option<H256>,
 	block_number: Option<u64>,
+	finalized: Option<bool>,
+	missed: bool,
+	chain: String,
+	block_root: Option<H256>,
+	proposer_index: Option<u64>,
+	graffiti: Option<String>,
+	randao_reveal: Option<Vec<u8>>,
 }
 
 impl From<DbSlot> for Slot {
@@ -32,16 +48,48 @@ impl From<DbSlot> for Slot {
 			height: db_slot.height as u64,
 			block_hash: db_slot.block_hash.map(|h| h.into()),
 			block_number: db_slot.block_number.map(|n| n as u64),
+			finalized: db_slot.finalized,
+			missed: db_slot.missed,
+			chain: db_slot.chain,
+			block_root: db_slot.block_root.map(|r| r.into()),
+			proposer_index: db_slot.proposer_index.map(|i| i as u64),
+			graffiti: db_slot.graffiti,
+			randao_reveal: db_slot.randao_reveal,
 		}
 	}
 }
 
 impl Slot {
+	/// Return the name of the chain the slot belongs to
+	pub fn chain(&self) -> &str {
+		&self.chain
+	}
+
 	/// Return the height of the slot
 	pub fn height(&self) -> u64 {
 		self.height
 	}
 
+	/// Return the root of the slot's beacon block, None if the slot was missed
+	pub fn block_root(&self) -> Option<H256> {
+		self.block_root
+	}
+
+	/// Return the index of the validator that proposed the block, or that missed the proposal
+	pub fn proposer_index(&self) -> Option<u64> {
+		self.proposer_index
+	}
+
+	/// Return the graffiti of the slot's beacon block
+	pub fn graffiti(&self) -> Option<&str> {
+		self.graffiti.as_deref()
+	}
+
+	/// Return the randao reveal of the slot's beacon block
+	pub fn randao_reveal(&self) -> Option<&[u8]> {
+		self.randao_reveal.as_deref()
+	}
+
 	/// Return the hash of the slot's execution block
 	pub fn block_hash(&self) -> Option<H256> {
 		self.block_hash
@@ -52,17 +100,91 @@ impl Slot {
 		self.block_number
 	}
 
-	/// Return the highest slot from db
-	pub fn get_highest(conn: &PgConnection) -> QueryResult<Slot> {
-		let slot = dsl_slots.order(slots::height.desc()).first::<DbSlot>(conn)?;
+	/// Return true if the slot is behind the finalized checkpoint
+	///
+	/// Return None if finality was not tracked when the slot was stored
+	pub fn is_finalized(&self) -> Option<bool> {
+		self.finalized
+	}
+
+	/// Return true if no block was proposed at this slot
+	pub fn is_missed(&self) -> bool {
+		self.missed
+	}
+
+	/// Return the highest slot of `chain` from db
+	pub fn get_highest(conn: &PgConnection, chain: &str) -> QueryResult<Slot> {
+		let slot = dsl_slots
+			.filter(slots::chain.eq(chain))
+			.order(slots::height.desc())
+			.first::<DbSlot>(conn)?;
 
 		Ok(slot.into())
 	}
 
 	/// Return an unique slot from db
-	pub fn get(conn: &PgConnection, height: u64) -> QueryResult<Slot> {
-		let slot = dsl_slots.find(height as i64).first::<DbSlot>(conn)?;
+	pub fn get(conn: &PgConnection, chain: &str, height: u64) -> QueryResult<Slot> {
+		let slot = dsl_slots.find((chain, height as i64)).first::<DbSlot>(conn)?;
 
 		Ok(slot.into())
 	}
+
+	/// Return the highest finalized slot of `chain` from db
+	pub fn get_highest_finalized(conn: &PgConnection, chain: &str) -> QueryResult<Slot> {
+		let slot = dsl_slots
+			.filter(slots::chain.eq(chain))
+			.filter(slots::finalized.eq(true))
+			.order(slots::height.desc())
+			.first::<DbSlot>(conn)?;
+
+		Ok(slot.into())
+	}
+
+	/// Return all the provisional slots of `chain` with a height lower or equal to `height`
+	pub fn list_provisional_up_to(
+		conn: &PgConnection,
+		chain: &str,
+		height: u64,
+	) -> QueryResult<Vec<Slot>> {
+		let db_slots: Vec<DbSlot> = dsl_slots
+			.filter(slots::chain.eq(chain))
+			.filter(slots::finalized.eq(false))
+			.filter(slots::height.le(height as i64))
+			.order(slots::height.asc())
+			.load(conn)?;
+
+		Ok(db_slots.into_iter().map(|s| s.into()).collect())
+	}
+
+	/// Return the slots of `chain` from `from_height` whose proposal was missed by one of
+	/// `proposer_indexes`, oldest first
+	pub fn list_missed_proposals(
+		conn: &PgConnection,
+		chain: &str,
+		proposer_indexes: &[u64],
+		from_height: u64,
+	) -> QueryResult<Vec<Slot>> {
+		let proposer_indexes: Vec<i64> = proposer_indexes.iter().map(|i| *i as i64).collect();
+
+		let db_slots: Vec<DbSlot> = dsl_slots
+			.filter(slots::chain.eq(chain))
+			.filter(slots::missed.eq(true))
+			.filter(slots::proposer_index.eq_any(proposer_indexes))
+			.filter(slots::height.ge(from_height as i64))
+			.order(slots::height.asc())
+			.load(conn)?;
+
+		Ok(db_slots.into_iter().map(|s| s.into()).collect())
+	}
+
+	/// Return the ranges of slots of `chain` that were never synced
+	///
+	/// Missed slots are stored as such, so any hole between the lowest and the highest stored slot
+	/// is a slot that failed to be synced.
+	pub fn list_missing_ranges(
+		conn: &PgConnection,
+		chain: &str,
+	) -> QueryResult<Vec<RangeInclusive<u64>>> {
+		list_missing_ranges(conn, "slots", "height", chain)
+	}
 }
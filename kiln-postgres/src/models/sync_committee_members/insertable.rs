@@ -0,0 +1,55 @@
+use diesel::{Insertable, PgConnection, QueryResult, RunQueryDsl};
+
+use crate::schema::sync_committee_members;
+
+/// A position in the sync committee of a period
+#[derive(Insertable)]
+#[table_name = "sync_committee_members"]
+pub struct NewSyncCommitteeMember {
+	period: i64,
+	position: i32,
+	validator_index: i64,
+	chain: String,
+}
+
+impl NewSyncCommitteeMember {
+	/// Return a new insertable member of the `chain` sync committee of `period`
+	pub fn new(
+		chain: &str,
+		period: u64,
+		position: u32,
+		validator_index: u64,
+	) -> NewSyncCommitteeMember {
+		NewSyncCommitteeMember {
+			period: period as i64,
+			position: position as i32,
+			validator_index: validator_index as i64,
+			chain: chain.to_string(),
+		}
+	}
+}
+
+/// An wrapper around the members of a sync committee
+pub struct NewSyncCommitteeMembers(Vec<NewSyncCommitteeMember>);
+
+impl NewSyncCommitteeMembers {
+	/// Insert an array of members in db
+	///
+	/// On conflict do nothing, a committee never changes once computed
+	pub fn batch_insert(&self, conn: &PgConnection) -> QueryResult<usize> {
+		diesel::insert_into(sync_committee_members::table)
+			.values(&self.0)
+			.on_conflict_do_nothing()
+			.execute(conn)
+	}
+}
+
+impl FromIterator<NewSyncCommitteeMember> for NewSyncCommitteeMembers {
+	fn from_iter<T: IntoIterator<Item = NewSyncCommitteeMember>>(iter: T) -> Self {
+		let mut members = vec![];
+		for m in iter {
+			members.push(m);
+		}
+		NewSyncCommitteeMembers(members)
+	}
+}
@@ -0,0 +1,90 @@
+use diesel::{
+	ExpressionMethods, Identifiable, PgConnection, QueryDsl, QueryResult, Queryable, RunQueryDsl,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::schema::{
+	sync_committee_members,
+	sync_committee_members::dsl::sync_committee_members as dsl_sync_committee_members,
+};
+
+#[derive(Queryable, Identifiable)]
+#[primary_key(chain, period, position)]
+#[table_name = "sync_committee_members"]
+struct DbSyncCommitteeMember {
+	period: i64,
+	position: i32,
+	validator_index: i64,
+	chain: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SyncCommitteeMember {
+	period: u64,
+	position: u32,
+	validator_index: u64,
+	chain: String,
+}
+
+impl From<DbSyncCommitteeMember> for SyncCommitteeMember {
+	fn from(db_member: DbSyncCommitteeMember) -> Self {
+		SyncCommitteeMember {
+			period: db_member.period as u64,
+			position: db_member.position as u32,
+			validator_index: db_member.validator_index as u64,
+			chain: db_member.chain,
+		}
+	}
+}
+
+impl SyncCommitteeMember {
+	/// Return the sync committee period
+	pub fn period(&self) -> u64 {
+		self.period
+	}
+
+	/// Return the position of the member in the committee
+	pub fn position(&self) -> u32 {
+		self.position
+	}
+
+	/// Return the index of the member
+	pub fn validator_index(&self) -> u64 {
+		self.validator_index
+	}
+
+	/// Return the members of the sync committee of `period`, ordered by position
+	///
+	/// Empty if the committee was not stored yet
+	pub fn list(
+		conn: &PgConnection,
+		chain: &str,
+		period: u64,
+	) -> QueryResult<Vec<SyncCommitteeMember>> {
+		let db_members: Vec<DbSyncCommitteeMember> = dsl_sync_committee_members
+			.filter(sync_committee_members::chain.eq(chain))
+			.filter(sync_committee_members::period.eq(period as i64))
+			.order(sync_committee_members::position.asc())
+			.load(conn)?;
+
+		Ok(db_members.into_iter().map(|m| m.into()).collect())
+	}
+
+	/// Return the positions a validator held in sync committees, oldest period first
+	pub fn list_for_validator(
+		conn: &PgConnection,
+		chain: &str,
+		validator_index: u64,
+	) -> QueryResult<Vec<SyncCommitteeMember>> {
+		let db_members: Vec<DbSyncCommitteeMember> = dsl_sync_committee_members
+			.filter(sync_committee_members::chain.eq(chain))
+			.filter(sync_committee_members::validator_index.eq(validator_index as i64))
+			.order((
+				sync_committee_members::period.asc(),
+				sync_committee_members::position.asc(),
+			))
+			.load(conn)?;
+
+		Ok(db_members.into_iter().map(|m| m.into()).collect())
+	}
+}
@@ -0,0 +1,72 @@
+use diesel::{ExpressionMethods, Insertable, PgConnection, QueryDsl, QueryResult, RunQueryDsl};
+
+use crate::schema::{
+	sync_participations, sync_participations::dsl::sync_participations as dsl_sync_participations,
+};
+
+/// Whether a sync committee member signed the sync aggregate of the block at `slot`
+#[derive(Insertable)]
+#[table_name = "sync_participations"]
+pub struct NewSyncParticipation {
+	slot: i64,
+	position: i32,
+	validator_index: i64,
+	participated: bool,
+	chain: String,
+}
+
+impl NewSyncParticipation {
+	/// Return a new insertable participation of a `chain` sync committee member
+	pub fn new(
+		chain: &str,
+		slot: u64,
+		position: u32,
+		validator_index: u64,
+		participated: bool,
+	) -> NewSyncParticipation {
+		NewSyncParticipation {
+			slot: slot as i64,
+			position: position as i32,
+			validator_index: validator_index as i64,
+			participated,
+			chain: chain.to_string(),
+		}
+	}
+
+	/// Delete the participations of the block at `slot`
+	///
+	/// Used when the block is replaced before finality
+	pub fn delete_at(conn: &PgConnection, chain: &str, slot: u64) -> QueryResult<usize> {
+		diesel::delete(
+			dsl_sync_participations
+				.filter(sync_participations::chain.eq(chain))
+				.filter(sync_participations::slot.eq(slot as i64)),
+		)
+		.execute(conn)
+	}
+}
+
+/// An wrapper around the participations of a sync aggregate
+pub struct NewSyncParticipations(Vec<NewSyncParticipation>);
+
+impl NewSyncParticipations {
+	/// Insert an array of participations in db
+	///
+	/// On conflict do nothing
+	pub fn batch_insert(&self, conn: &PgConnection) -> QueryResult<usize> {
+		diesel::insert_into(sync_participations::table)
+			.values(&self.0)
+			.on_conflict_do_nothing()
+			.execute(conn)
+	}
+}
+
+impl FromIterator<NewSyncParticipation> for NewSyncParticipations {
+	fn from_iter<T: IntoIterator<Item = NewSyncParticipation>>(iter: T) -> Self {
+		let mut participations = vec![];
+		for p in iter {
+			participations.push(p);
+		}
+		NewSyncParticipations(participations)
+	}
+}
@@ -0,0 +1,102 @@
+use diesel::{
+	ExpressionMethods, Identifiable, PgConnection, QueryDsl, QueryResult, Queryable, RunQueryDsl,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::schema::{
+	sync_participations, sync_participations::dsl::sync_participations as dsl_sync_participations,
+};
+
+#[derive(Queryable, Identifiable)]
+#[primary_key(chain, slot, position)]
+#[table_name = "sync_participations"]
+struct DbSyncParticipation {
+	slot: i64,
+	position: i32,
+	validator_index: i64,
+	participated: bool,
+	chain: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SyncParticipation {
+	slot: u64,
+	position: u32,
+	validator_index: u64,
+	participated: bool,
+	chain: String,
+}
+
+impl From<DbSyncParticipation> for SyncParticipation {
+	fn from(db_participation: DbSyncParticipation) -> Self {
+		SyncParticipation {
+			slot: db_participation.slot as u64,
+			position: db_participation.position as u32,
+			validator_index: db_participation.validator_index as u64,
+			participated: db_participation.participated,
+			chain: db_participation.chain,
+		}
+	}
+}
+
+impl SyncParticipation {
+	/// Return the slot of the block
+	pub fn slot(&self) -> u64 {
+		self.slot
+	}
+
+	/// Return the index of the sync committee member
+	pub fn validator_index(&self) -> u64 {
+		self.validator_index
+	}
+
+	/// Return true if the member signed the sync aggregate
+	pub fn has_participated(&self) -> bool {
+		self.participated
+	}
+
+	/// Return the participations of a validator between `from_slot` and `to_slot` included,
+	/// oldest first
+	///
+	/// A validator holding several positions in the committee has one participation per position
+	pub fn list_for_validator(
+		conn: &PgConnection,
+		chain: &str,
+		validator_index: u64,
+		from_slot: u64,
+		to_slot: u64,
+	) -> QueryResult<Vec<SyncParticipation>> {
+		let db_participations: Vec<DbSyncParticipation> = dsl_sync_participations
+			.filter(sync_participations::chain.eq(chain))
+			.filter(sync_participations::validator_index.eq(validator_index as i64))
+			.filter(sync_participations::slot.between(from_slot as i64, to_slot as i64))
+			.order((
+				sync_participations::slot.asc(),
+				sync_participations::position.asc(),
+			))
+			.load(conn)?;
+
+		Ok(db_participations.into_iter().map(|p| p.into()).collect())
+	}
+
+	/// Return the number of sync aggregates a validator signed and the number it was expected to
+	/// sign between `from_slot` and `to_slot` included
+	pub fn count_for_validator(
+		conn: &PgConnection,
+		chain: &str,
+		validator_index: u64,
+		from_slot: u64,
+		to_slot: u64,
+	) -> QueryResult<(u64, u64)> {
+		let participated: Vec<bool> = dsl_sync_participations
+			.filter(sync_participations::chain.eq(chain))
+			.filter(sync_participations::validator_index.eq(validator_index as i64))
+			.filter(sync_participations::slot.between(from_slot as i64, to_slot as i64))
+			.select(sync_participations::participated)
+			.load(conn)?;
+
+		let signed = participated.iter().filter(|p| **p).count() as u64;
+
+		Ok((signed, participated.len() as u64))
+	}
+}
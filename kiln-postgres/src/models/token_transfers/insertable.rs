@@ -0,0 +1,105 @@
+use diesel::{Insertable, PgConnection, QueryResult, RunQueryDsl};
+use primitive_types::{H160, H256, U256};
+
+use crate::{
+	models::{u256_to_vec_u8, Hash160, Hash256},
+	schema::token_transfers,
+};
+
+/// The token standard a transfer was emitted under
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenStandard {
+	/// Fungible token, the transfer moves an amount
+	Erc20,
+	/// Non fungible token, the transfer moves a single token id
+	Erc721,
+	/// Multi token, the transfer moves an amount of a token id
+	Erc1155,
+}
+
+impl TokenStandard {
+	/// Return the name stored in db
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			TokenStandard::Erc20 => "erc20",
+			TokenStandard::Erc721 => "erc721",
+			TokenStandard::Erc1155 => "erc1155",
+		}
+	}
+}
+
+/// A token transfer decoded from a log emitted during `transaction_hash`
+#[derive(Insertable)]
+#[table_name = "token_transfers"]
+pub struct NewTokenTransfer {
+	transaction_hash: Hash256,
+	log_index: i64,
+	batch_index: i32,
+	token: Hash160,
+	standard: String,
+	from: Hash160,
+	to: Hash160,
+	token_id: Option<Vec<u8>>,
+	value: Vec<u8>,
+	chain: String,
+}
+
+impl NewTokenTransfer {
+	/// Return a new insertable token transfer of `chain`
+	///
+	/// `log_index` is the index of the log in its block, `batch_index` the position of the
+	/// transfer in an ERC-1155 TransferBatch and 0 otherwise.
+	/// `token_id` is None for an ERC-20 transfer, `value` is 1 for an ERC-721 transfer
+	#[allow(clippy::too_many_arguments)]
+	pub fn new(
+		chain: &str,
+		transaction_hash: H256,
+		log_index: u64,
+		batch_index: u32,
+		token: H160,
+		standard: TokenStandard,
+		from: H160,
+		to: H160,
+		token_id: Option<U256>,
+		value: U256,
+	) -> NewTokenTransfer {
+		NewTokenTransfer {
+			transaction_hash: transaction_hash.into(),
+			log_index: log_index as i64,
+			batch_index: batch_index as i32,
+			token: token.into(),
+			standard: standard.as_str().to_string(),
+			from: from.into(),
+			to: to.into(),
+			token_id: token_id.map(u256_to_vec_u8),
+			value: u256_to_vec_u8(value),
+			chain: chain.to_string(),
+		}
+	}
+}
+
+/// An wrapper around an array of token transfers
+pub struct NewTokenTransfers(Vec<NewTokenTransfer>);
+
+impl NewTokenTransfers {
+	/// Insert an array of token transfers in db
+	///
+	/// Fail in case of conflict
+	pub fn batch_insert(&self, conn: &PgConnection) -> QueryResult<()> {
+		for chunk in self.0.chunks(1000) {
+			diesel::insert_into(token_transfers::table).values(chunk).execute(conn)?;
+		}
+
+		Ok(())
+	}
+}
+
+impl FromIterator<NewTokenTransfer> for NewTokenTransfers {
+	fn from_iter<T: IntoIterator<Item = NewTokenTransfer>>(iter: T) -> Self {
+		let mut transfers = vec![];
+		for t in iter {
+			transfers.push(t);
+		}
+		NewTokenTransfers(transfers)
+	}
+}
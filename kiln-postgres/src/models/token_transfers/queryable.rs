@@ -0,0 +1,185 @@
+use diesel::{
+	sql_types::{BigInt, Binary, Nullable, Text},
+	Identifiable, PgConnection, QueryResult, RunQueryDsl,
+};
+use primitive_types::{H160, H256, U256};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	models::{Hash160, Hash256},
+	schema::token_transfers,
+};
+
+#[derive(QueryableByName, Identifiable)]
+#[primary_key(transaction_hash, log_index, batch_index)]
+#[table_name = "token_transfers"]
+struct DbTokenTransfer {
+	#[sql_type = "BigInt"]
+	block_number: i64,
+	transaction_hash: Hash256,
+	log_index: i64,
+	batch_index: i32,
+	token: Hash160,
+	standard: String,
+	from: Hash160,
+	to: Hash160,
+	token_id: Option<Vec<u8>>,
+	value: Vec<u8>,
+	chain: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TokenTransfer {
+	block_number: u64,
+	transaction_hash: H256,
+	log_index: u64,
+	batch_index: u32,
+	token: H160,
+	standard: String,
+	from: H160,
+	to: H160,
+	token_id: Option<U256>,
+	value: U256,
+	chain: String,
+}
+
+impl From<DbTokenTransfer> for TokenTransfer {
+	fn from(db_transfer: DbTokenTransfer) -> Self {
+		TokenTransfer {
+			block_number: db_transfer.block_number as u64,
+			transaction_hash: db_transfer.transaction_hash.into(),
+			log_index: db_transfer.log_index as u64,
+			batch_index: db_transfer.batch_index as u32,
+			token: db_transfer.token.into(),
+			standard: db_transfer.standard,
+			from: db_transfer.from.into(),
+			to: db_transfer.to.into(),
+			token_id: db_transfer.token_id.map(|i| U256::from_little_endian(&i)),
+			value: U256::from_little_endian(&db_transfer.value),
+			chain: db_transfer.chain,
+		}
+	}
+}
+
+impl TokenTransfer {
+	/// Return the number of the block the transfer was emitted in
+	pub fn block_number(&self) -> u64 {
+		self.block_number
+	}
+
+	/// Return the hash of the transaction that emitted the transfer
+	pub fn transaction_hash(&self) -> H256 {
+		self.transaction_hash
+	}
+
+	/// Return the index of the transfer log in its block
+	pub fn log_index(&self) -> u64 {
+		self.log_index
+	}
+
+	/// Return the position of the transfer in an ERC-1155 TransferBatch, 0 otherwise
+	pub fn batch_index(&self) -> u32 {
+		self.batch_index
+	}
+
+	/// Return the address of the token contract
+	pub fn token(&self) -> H160 {
+		self.token
+	}
+
+	/// Return the standard of the token, e.g. `erc20`
+	pub fn standard(&self) -> &str {
+		&self.standard
+	}
+
+	/// Return the address the tokens were sent from, zero for a mint
+	pub fn from(&self) -> H160 {
+		self.from
+	}
+
+	/// Return the address the tokens were sent to, zero for a burn
+	pub fn to(&self) -> H160 {
+		self.to
+	}
+
+	/// Return the id of the token transfered, None for an ERC-20 transfer
+	pub fn token_id(&self) -> Option<U256> {
+		self.token_id
+	}
+
+	/// Return the amount of tokens transfered, 1 for an ERC-721 transfer
+	pub fn value(&self) -> U256 {
+		self.value
+	}
+
+	/// Return the token transfers from or to `address`, in chain order
+	///
+	/// Only the transfers emitted after the log at `after`, a (block number, log index) pair, are
+	/// returned if provided. The transfers of `limit` logs at most are returned, so that the
+	/// transfers of an ERC-1155 TransferBatch are never split between two pages.
+	/// Only transfers of `chain` are considered if provided
+	pub fn list_for_address(
+		conn: &PgConnection,
+		address: H160,
+		chain: Option<&str>,
+		after: Option<(u64, u64)>,
+		limit: u64,
+	) -> QueryResult<Vec<TokenTransfer>> {
+		let filter = r#"tt."from" = $1 OR tt."to" = $1"#;
+
+		list_page(conn, filter, address, chain, after, limit)
+	}
+
+	/// Return the transfers of the token contract at `token`, in chain order
+	///
+	/// Only the transfers emitted after the log at `after`, a (block number, log index) pair, are
+	/// returned if provided. The transfers of `limit` logs at most are returned.
+	/// Only transfers of `chain` are considered if provided
+	pub fn list_for_token(
+		conn: &PgConnection,
+		token: H160,
+		chain: Option<&str>,
+		after: Option<(u64, u64)>,
+		limit: u64,
+	) -> QueryResult<Vec<TokenTransfer>> {
+		list_page(conn, "tt.token = $1", token, chain, after, limit)
+	}
+}
+
+// Return a page of the transfers matching `filter`, where `$1` is bound to `address`
+//
+// `filter` is interpolated in the query and must never come from user input
+fn list_page(
+	conn: &PgConnection,
+	filter: &'static str,
+	address: H160,
+	chain: Option<&str>,
+	after: Option<(u64, u64)>,
+	limit: u64,
+) -> QueryResult<Vec<TokenTransfer>> {
+	let address: Hash160 = address.into();
+	let (after_block, after_log) =
+		after.map_or((None, None), |(b, l)| (Some(b as i64), Some(l as i64)));
+
+	let db_transfers: Vec<DbTokenTransfer> = diesel::sql_query(format!(
+		r#"SELECT * FROM (
+			SELECT tt.*, b."number" AS block_number,
+			DENSE_RANK() OVER (ORDER BY b."number", tt.log_index) AS log_rank
+			FROM token_transfers tt
+			JOIN transactions t ON t.hash = tt.transaction_hash
+			JOIN execution_blocks b ON b.hash = t.block_hash
+			WHERE ({filter}) AND ($2::VARCHAR IS NULL OR tt.chain = $2)
+			AND ($3::BIGINT IS NULL OR (b."number", tt.log_index) > ($3, $4))
+		) p
+		WHERE p.log_rank <= $5
+		ORDER BY p.block_number ASC, p.log_index ASC, p.batch_index ASC"#
+	))
+	.bind::<Binary, _>(address)
+	.bind::<Nullable<Text>, _>(chain)
+	.bind::<Nullable<BigInt>, _>(after_block)
+	.bind::<Nullable<BigInt>, _>(after_log)
+	.bind::<BigInt, _>(limit as i64)
+	.load(conn)?;
+
+	Ok(db_transfers.into_iter().map(|t| t.into()).collect())
+}
@@ -1,8 +1,9 @@
-use diesel::{ExpressionMethods, PgConnection, QueryDsl, QueryResult, RunQueryDsl};
+use diesel::{PgConnection, QueryResult, RunQueryDsl};
 use primitive_types::{H160, H256, U256};
+use serde::{Deserialize, Serialize};
 
 use crate::{
-	models::{Hash160, Hash256},
+	models::{u256_to_vec_u8, Hash160, Hash256},
 	schema::transactions,
 };
 
@@ -17,11 +18,33 @@ pub struct NewTransaction {
 	to: Option<Hash160>,
 	input: Vec<u8>,
 	value: Vec<u8>,
+	status: Option<bool>,
+	chain: String,
+	gas_used: Option<i64>,
+	cumulative_gas_used: Option<i64>,
+	effective_gas_price: Option<Vec<u8>>,
+	contract_address: Option<Hash160>,
+	nonce: Option<i64>,
+	gas: Option<i64>,
+	gas_price: Option<Vec<u8>>,
+	max_fee_per_gas: Option<Vec<u8>>,
+	max_priority_fee_per_gas: Option<Vec<u8>>,
+	transaction_type: Option<i32>,
+	access_list: Option<serde_json::Value>,
+}
+
+/// An address and the storage keys a transaction declared it would access (EIP-2930)
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AccessListItem {
+	pub address: H160,
+	pub storage_keys: Vec<H256>,
 }
 
 impl NewTransaction {
 	/// Return a new insertable Transaction
+	#[allow(clippy::too_many_arguments)]
 	pub fn new(
+		chain: &str,
 		hash: H256,
 		block_hash: H256,
 		index: u64,
@@ -38,23 +61,75 @@ impl NewTransaction {
 			to: to.map(|t| t.into()),
 			input,
 			value: u256_to_vec_u8(value),
+			status: None,
+			chain: chain.to_string(),
+			gas_used: None,
+			cumulative_gas_used: None,
+			effective_gas_price: None,
+			contract_address: None,
+			nonce: None,
+			gas: None,
+			gas_price: None,
+			max_fee_per_gas: None,
+			max_priority_fee_per_gas: None,
+			transaction_type: None,
+			access_list: None,
 		}
 	}
 
+	/// Add the gas and fee fields of the transaction
+	///
+	/// `transaction_type` is 0 for legacy transactions (None on older nodes), 1 for EIP-2930 and 2
+	/// for EIP-1559.
+	/// The max fees are only set by EIP-1559 transactions, the access list by typed transactions.
+	#[allow(clippy::too_many_arguments)]
+	pub fn with_fees(
+		mut self,
+		nonce: u64,
+		gas: u64,
+		gas_price: Option<U256>,
+		max_fee_per_gas: Option<U256>,
+		max_priority_fee_per_gas: Option<U256>,
+		transaction_type: Option<u32>,
+		access_list: Option<Vec<AccessListItem>>,
+	) -> NewTransaction {
+		self.nonce = Some(nonce as i64);
+		self.gas = Some(gas as i64);
+		self.gas_price = gas_price.map(u256_to_vec_u8);
+		self.max_fee_per_gas = max_fee_per_gas.map(u256_to_vec_u8);
+		self.max_priority_fee_per_gas = max_priority_fee_per_gas.map(u256_to_vec_u8);
+		self.transaction_type = transaction_type.map(|t| t as i32);
+		// Safe to unwrap because addresses and storage keys always serialize
+		self.access_list = access_list.map(|a| serde_json::to_value(a).unwrap());
+		self
+	}
+
+	/// Add the outcome of the transaction execution, as reported by its receipt
+	///
+	/// `status` is None before Byzantium, `contract_address` is the address of the contract created
+	/// by the transaction, if any
+	pub fn with_receipt(
+		mut self,
+		status: Option<bool>,
+		gas_used: Option<u64>,
+		cumulative_gas_used: u64,
+		effective_gas_price: Option<U256>,
+		contract_address: Option<H160>,
+	) -> NewTransaction {
+		self.status = status;
+		self.gas_used = gas_used.map(|g| g as i64);
+		self.cumulative_gas_used = Some(cumulative_gas_used as i64);
+		self.effective_gas_price = effective_gas_price.map(u256_to_vec_u8);
+		self.contract_address = contract_address.map(|a| a.into());
+		self
+	}
+
 	/// Insert a new transaction on db
 	///
 	/// Fail in case of conflict
 	pub fn insert(&self, conn: &PgConnection) -> QueryResult<usize> {
 		diesel::insert_into(transactions::table).values(self).execute(conn)
 	}
-
-	/// Set a transaction status
-	pub fn set_status(conn: &PgConnection, hash: H256, status: bool) -> QueryResult<usize> {
-		let hash: Hash256 = hash.into();
-		diesel::update(transactions::dsl::transactions.find(hash))
-			.set(transactions::status.eq(Some(status)))
-			.execute(conn)
-	}
 }
 
 pub struct NewTransactions(Vec<NewTransaction>);
@@ -78,21 +153,3 @@ impl FromIterator<NewTransaction> for NewTransactions {
 		NewTransactions(transactions)
 	}
 }
-
-// U256 internal representation is `&[u64; 4]`.
-// We need it as `[u8]` to be stored as BYTEA in db.
-// Keep the pointed memory untouched but reorganise `len`, `capacity` and type of the reference.
-fn u256_to_vec_u8(value: U256) -> Vec<u8> {
-	let value_as_vec_u64: Vec<u64> = value.0.to_vec();
-	let ratio = std::mem::size_of::<u64>() / std::mem::size_of::<u8>();
-
-	let length = value_as_vec_u64.len() * ratio;
-	let capacity = value_as_vec_u64.capacity() * ratio;
-	let ptr = value_as_vec_u64.as_ptr() as *mut u8;
-
-	std::mem::forget(value_as_vec_u64);
-
-	// Safe because we forget the original reference before creating the new one
-	// and because we adjusted `lenght` and `capacity` proportionaly.
-	unsafe { Vec::from_raw_parts(ptr, length, capacity) }
-}
@@ -5,8 +5,11 @@ use primitive_types::{H160, H256, U256};
 use serde::{Deserialize, Serialize};
 
 use crate::{
-	models::{Hash160, Hash256},
-	schema::{transactions, transactions::dsl::transactions as dsl_transactions},
+	models::{AccessListItem, Hash160, Hash256},
+	schema::{
+		execution_blocks, execution_blocks::dsl::execution_blocks as dsl_blocks, transactions,
+		transactions::dsl::transactions as dsl_transactions,
+	},
 };
 
 #[derive(Queryable, Identifiable)]
@@ -21,6 +24,18 @@ struct DbTransaction {
 	input: Vec<u8>,
 	value: Vec<u8>,
 	status: Option<bool>,
+	chain: String,
+	gas_used: Option<i64>,
+	cumulative_gas_used: Option<i64>,
+	effective_gas_price: Option<Vec<u8>>,
+	contract_address: Option<Hash160>,
+	nonce: Option<i64>,
+	gas: Option<i64>,
+	gas_price: Option<Vec<u8>>,
+	max_fee_per_gas: Option<Vec<u8>>,
+	max_priority_fee_per_gas: Option<Vec<u8>>,
+	transaction_type: Option<i32>,
+	access_list: Option<serde_json::Value>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -33,6 +48,18 @@ pub struct Transaction {
 	input: Vec<u8>,
 	value: U256,
 	status: Option<bool>,
+	chain: String,
+	gas_used: Option<u64>,
+	cumulative_gas_used: Option<u64>,
+	effective_gas_price: Option<U256>,
+	contract_address: Option<H160>,
+	nonce: Option<u64>,
+	gas: Option<u64>,
+	gas_price: Option<U256>,
+	max_fee_per_gas: Option<U256>,
+	max_priority_fee_per_gas: Option<U256>,
+	transaction_type: Option<u32>,
+	access_list: Option<Vec<AccessListItem>>,
 }
 
 impl From<DbTransaction> for Transaction {
@@ -46,19 +73,42 @@ impl From<DbTransaction> for Transaction {
 			input: db_transaction.input,
 			value: U256::from_little_endian(&db_transaction.value),
 			status: db_transaction.status,
+			chain: db_transaction.chain,
+			gas_used: db_transaction.gas_used.map(|g| g as u64),
+			cumulative_gas_used: db_transaction.cumulative_gas_used.map(|g| g as u64),
+			effective_gas_price: db_transaction
+				.effective_gas_price
+				.map(|p| U256::from_little_endian(&p)),
+			contract_address: db_transaction.contract_address.map(|a| a.into()),
+			nonce: db_transaction.nonce.map(|n| n as u64),
+			gas: db_transaction.gas.map(|g| g as u64),
+			gas_price: db_transaction.gas_price.map(|p| U256::from_little_endian(&p)),
+			max_fee_per_gas: db_transaction.max_fee_per_gas.map(|f| U256::from_little_endian(&f)),
+			max_priority_fee_per_gas: db_transaction
+				.max_priority_fee_per_gas
+				.map(|f| U256::from_little_endian(&f)),
+			transaction_type: db_transaction.transaction_type.map(|t| t as u32),
+			access_list: db_transaction.access_list.and_then(|a| serde_json::from_value(a).ok()),
 		}
 	}
 }
 
 impl Transaction {
+	/// Return all the transactions sent by `address`
+	///
+	/// Only transactions of `chain` are returned if provided
 	pub fn list_all_from_address(
 		conn: &PgConnection,
 		address: H160,
+		chain: Option<&str>,
 	) -> QueryResult<Vec<Transaction>> {
 		let address: Hash160 = address.into();
 
-		let db_transactions: Vec<DbTransaction> =
-			dsl_transactions.filter(transactions::from.eq(address)).load(conn)?;
+		let mut query = dsl_transactions.filter(transactions::from.eq(address)).into_boxed();
+		if let Some(chain) = chain {
+			query = query.filter(transactions::chain.eq(chain));
+		}
+		let db_transactions: Vec<DbTransaction> = query.load(conn)?;
 
 		let transactions: Vec<Transaction> =
 			db_transactions.into_iter().map(|t| t.into()).collect();
@@ -66,12 +116,22 @@ impl Transaction {
 		Ok(transactions)
 	}
 
-	pub fn list_all_distinct_issuer(conn: &PgConnection) -> QueryResult<Vec<H160>> {
-		let db_hashs: Vec<Option<Hash160>> = dsl_transactions
+	/// Return every address that sent at least one transaction
+	///
+	/// Only transactions of `chain` are considered if provided
+	pub fn list_all_distinct_issuer(
+		conn: &PgConnection,
+		chain: Option<&str>,
+	) -> QueryResult<Vec<H160>> {
+		let mut query = dsl_transactions
 			.select(transactions::from)
 			.filter(transactions::from.is_not_null())
 			.distinct()
-			.load(conn)?;
+			.into_boxed();
+		if let Some(chain) = chain {
+			query = query.filter(transactions::chain.eq(chain));
+		}
+		let db_hashs: Vec<Option<Hash160>> = query.load(conn)?;
 
 		// Safe to use `unwrap_unchecked` because we filtered NOT NULL in the query
 		let addresses =
@@ -80,6 +140,41 @@ impl Transaction {
 		Ok(addresses)
 	}
 
+	/// Return the successful transactions of `chain` sent to `address`, in chain order
+	pub fn list_successful_to(
+		conn: &PgConnection,
+		chain: &str,
+		address: H160,
+	) -> QueryResult<Vec<Transaction>> {
+		let address: Hash160 = address.into();
+
+		let db_transactions: Vec<DbTransaction> = dsl_transactions
+			.inner_join(dsl_blocks)
+			.filter(transactions::chain.eq(chain))
+			.filter(transactions::to.eq(address))
+			.filter(transactions::status.eq(true))
+			.order((execution_blocks::number.asc(), transactions::index.asc()))
+			.select(transactions::all_columns)
+			.load(conn)?;
+
+		Ok(db_transactions.into_iter().map(|t| t.into()).collect())
+	}
+
+	/// Return the hash of the transaction
+	pub fn hash(&self) -> H256 {
+		self.hash
+	}
+
+	/// Return the value transfered by the transaction, in wei
+	pub fn value(&self) -> U256 {
+		self.value
+	}
+
+	/// Return the name of the chain the transaction belongs to
+	pub fn chain(&self) -> &str {
+		&self.chain
+	}
+
 	/// Return the address of the transaction recipient
 	pub fn to(&self) -> Option<H160> {
 		self.to
@@ -89,4 +184,66 @@ impl Transaction {
 	pub fn input(&self) -> Vec<u8> {
 		self.input.clone()
 	}
+
+	/// Return true if the transaction succeeded, None if its receipt was never fetched
+	pub fn status(&self) -> Option<bool> {
+		self.status
+	}
+
+	/// Return the gas used by the transaction alone
+	pub fn gas_used(&self) -> Option<u64> {
+		self.gas_used
+	}
+
+	/// Return the gas used in the block up to this transaction included
+	pub fn cumulative_gas_used(&self) -> Option<u64> {
+		self.cumulative_gas_used
+	}
+
+	/// Return the price paid per unit of gas, in wei
+	pub fn effective_gas_price(&self) -> Option<U256> {
+		self.effective_gas_price
+	}
+
+	/// Return the address of the contract created by the transaction
+	pub fn contract_address(&self) -> Option<H160> {
+		self.contract_address
+	}
+
+	/// Return the nonce of the sender at the time of the transaction
+	pub fn nonce(&self) -> Option<u64> {
+		self.nonce
+	}
+
+	/// Return the maximum amount of gas the transaction could use
+	pub fn gas(&self) -> Option<u64> {
+		self.gas
+	}
+
+	/// Return the gas price of a legacy or EIP-2930 transaction, in wei
+	///
+	/// Nodes also report it for EIP-1559 transactions, as the effective gas price
+	pub fn gas_price(&self) -> Option<U256> {
+		self.gas_price
+	}
+
+	/// Return the maximum fee per gas of an EIP-1559 transaction, in wei
+	pub fn max_fee_per_gas(&self) -> Option<U256> {
+		self.max_fee_per_gas
+	}
+
+	/// Return the maximum priority fee per gas of an EIP-1559 transaction, in wei
+	pub fn max_priority_fee_per_gas(&self) -> Option<U256> {
+		self.max_priority_fee_per_gas
+	}
+
+	/// Return the type of the transaction: 0 or None for legacy, 1 for EIP-2930, 2 for EIP-1559
+	pub fn transaction_type(&self) -> Option<u32> {
+		self.transaction_type
+	}
+
+	/// Return the access list of a typed transaction
+	pub fn access_list(&self) -> Option<&[AccessListItem]> {
+		self.access_list.as_deref()
+	}
 }
@@ -4,7 +4,7 @@ use diesel::{
 	serialize::{self, IsNull, Output, ToSql},
 	sql_types::Binary,
 };
-use primitive_types::{H160, H256};
+use primitive_types::{H160, H256, U256};
 use std::io::Write;
 
 // H260
@@ -74,3 +74,12 @@ impl FromSql<Binary, Pg> for Hash160 {
 		}
 	}
 }
+
+// U256
+
+// U256 is stored as its 32 little-endian bytes in a BYTEA column
+pub(crate) fn u256_to_vec_u8(value: U256) -> Vec<u8> {
+	let mut bytes = vec![0u8; 32];
+	value.to_little_endian(&mut bytes);
+	bytes
+}
@@ -0,0 +1,58 @@
+use diesel::{Insertable, PgConnection, QueryResult, RunQueryDsl};
+use eth2::types::ValidatorData;
+
+use crate::schema::validator_balances;
+
+/// The balance of a validator at the start of an epoch
+#[derive(Insertable)]
+#[table_name = "validator_balances"]
+pub struct NewValidatorBalance {
+	validator_index: i64,
+	epoch: i64,
+	balance: i64,
+	effective_balance: i64,
+	chain: String,
+}
+
+impl NewValidatorBalance {
+	/// Return a new insertable balance of a `chain` validator at `epoch`
+	pub fn new(chain: &str, epoch: u64, data: &ValidatorData) -> NewValidatorBalance {
+		NewValidatorBalance {
+			validator_index: data.index as i64,
+			epoch: epoch as i64,
+			balance: data.balance as i64,
+			effective_balance: data.validator.effective_balance as i64,
+			chain: chain.to_string(),
+		}
+	}
+}
+
+/// An wrapper around an array of validator balances
+pub struct NewValidatorBalances(Vec<NewValidatorBalance>);
+
+impl NewValidatorBalances {
+	/// Insert an array of validator balances in db
+	///
+	/// Balances already stored for the same validator and epoch are left untouched
+	pub fn batch_insert(&self, conn: &PgConnection) -> QueryResult<usize> {
+		let mut inserted = 0;
+		for chunk in self.0.chunks(1000) {
+			inserted += diesel::insert_into(validator_balances::table)
+				.values(chunk)
+				.on_conflict_do_nothing()
+				.execute(conn)?;
+		}
+
+		Ok(inserted)
+	}
+}
+
+impl FromIterator<NewValidatorBalance> for NewValidatorBalances {
+	fn from_iter<T: IntoIterator<Item = NewValidatorBalance>>(iter: T) -> Self {
+		let mut balances = vec![];
+		for b in iter {
+			balances.push(b);
+		}
+		NewValidatorBalances(balances)
+	}
+}
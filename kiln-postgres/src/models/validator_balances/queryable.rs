@@ -0,0 +1,124 @@
+use diesel::{
+	dsl::max, ExpressionMethods, Identifiable, OptionalExtension, PgConnection, QueryDsl,
+	QueryResult, Queryable, RunQueryDsl,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::schema::{
+	validator_balances, validator_balances::dsl::validator_balances as dsl_validator_balances,
+};
+
+#[derive(Queryable, Identifiable)]
+#[primary_key(chain, validator_index, epoch)]
+#[table_name = "validator_balances"]
+struct DbValidatorBalance {
+	validator_index: i64,
+	epoch: i64,
+	balance: i64,
+	effective_balance: i64,
+	chain: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ValidatorBalance {
+	validator_index: u64,
+	epoch: u64,
+	balance: u64,
+	effective_balance: u64,
+	chain: String,
+}
+
+impl From<DbValidatorBalance> for ValidatorBalance {
+	fn from(db_balance: DbValidatorBalance) -> Self {
+		ValidatorBalance {
+			validator_index: db_balance.validator_index as u64,
+			epoch: db_balance.epoch as u64,
+			balance: db_balance.balance as u64,
+			effective_balance: db_balance.effective_balance as u64,
+			chain: db_balance.chain,
+		}
+	}
+}
+
+impl ValidatorBalance {
+	/// Return the epoch of the snapshot
+	pub fn epoch(&self) -> u64 {
+		self.epoch
+	}
+
+	/// Return the balance of the validator, in gwei
+	pub fn balance(&self) -> u64 {
+		self.balance
+	}
+
+	/// Return the effective balance of the validator, in gwei
+	pub fn effective_balance(&self) -> u64 {
+		self.effective_balance
+	}
+
+	/// Return the highest epoch with stored balances
+	pub fn get_highest_epoch(conn: &PgConnection, chain: &str) -> QueryResult<Option<u64>> {
+		let epoch: Option<i64> = dsl_validator_balances
+			.filter(validator_balances::chain.eq(chain))
+			.select(max(validator_balances::epoch))
+			.first(conn)?;
+
+		Ok(epoch.map(|e| e as u64))
+	}
+
+	/// Return the balances of a validator between `from_epoch` and `to_epoch` included, oldest
+	/// first
+	pub fn list(
+		conn: &PgConnection,
+		chain: &str,
+		validator_index: u64,
+		from_epoch: u64,
+		to_epoch: u64,
+	) -> QueryResult<Vec<ValidatorBalance>> {
+		let db_balances: Vec<DbValidatorBalance> = dsl_validator_balances
+			.filter(validator_balances::chain.eq(chain))
+			.filter(validator_balances::validator_index.eq(validator_index as i64))
+			.filter(validator_balances::epoch.between(from_epoch as i64, to_epoch as i64))
+			.order(validator_balances::epoch.asc())
+			.load(conn)?;
+
+		Ok(db_balances.into_iter().map(|b| b.into()).collect())
+	}
+
+	/// Return the balance of a validator at `epoch`
+	pub fn get(
+		conn: &PgConnection,
+		chain: &str,
+		validator_index: u64,
+		epoch: u64,
+	) -> QueryResult<ValidatorBalance> {
+		let db_balance: DbValidatorBalance = dsl_validator_balances
+			.filter(validator_balances::chain.eq(chain))
+			.filter(validator_balances::validator_index.eq(validator_index as i64))
+			.filter(validator_balances::epoch.eq(epoch as i64))
+			.first(conn)?;
+
+		Ok(db_balance.into())
+	}
+
+	/// Return the balance a validator earned between `from_epoch` and `to_epoch`, in gwei
+	///
+	/// Negative if the validator was penalized. Deposits and withdrawals made in between are
+	/// counted as earnings.
+	/// Return None if one of the two snapshots is missing.
+	pub fn earned_between(
+		conn: &PgConnection,
+		chain: &str,
+		validator_index: u64,
+		from_epoch: u64,
+		to_epoch: u64,
+	) -> QueryResult<Option<i64>> {
+		let from = Self::get(conn, chain, validator_index, from_epoch).optional()?;
+		let to = Self::get(conn, chain, validator_index, to_epoch).optional()?;
+
+		Ok(match (from, to) {
+			(Some(from), Some(to)) => Some(to.balance as i64 - from.balance as i64),
+			_ => None,
+		})
+	}
+}
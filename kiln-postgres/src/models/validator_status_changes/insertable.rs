@@ -0,0 +1,74 @@
+use diesel::{Insertable, PgConnection, QueryResult, RunQueryDsl};
+
+use crate::schema::validator_status_changes;
+
+/// A validator observed with a new status
+#[derive(Insertable)]
+#[table_name = "validator_status_changes"]
+pub struct NewValidatorStatusChange {
+	validator_index: i64,
+	epoch: i64,
+	old_status: Option<String>,
+	new_status: String,
+	chain: String,
+}
+
+impl NewValidatorStatusChange {
+	/// Return a new insertable status change of a `chain` validator observed at `epoch`
+	///
+	/// `old_status` is None the first time the validator is seen
+	pub fn new(
+		chain: &str,
+		validator_index: u64,
+		epoch: u64,
+		old_status: Option<String>,
+		new_status: String,
+	) -> NewValidatorStatusChange {
+		NewValidatorStatusChange {
+			validator_index: validator_index as i64,
+			epoch: epoch as i64,
+			old_status,
+			new_status,
+			chain: chain.to_string(),
+		}
+	}
+}
+
+/// An wrapper around an array of validator status changes
+pub struct NewValidatorStatusChanges(Vec<NewValidatorStatusChange>);
+
+impl NewValidatorStatusChanges {
+	/// Return the number of status changes
+	pub fn len(&self) -> usize {
+		self.0.len()
+	}
+
+	/// Return true if there is no status change
+	pub fn is_empty(&self) -> bool {
+		self.0.is_empty()
+	}
+
+	/// Insert an array of status changes in db
+	///
+	/// A status already entered by a validator is left untouched
+	pub fn batch_insert(&self, conn: &PgConnection) -> QueryResult<()> {
+		for chunk in self.0.chunks(1000) {
+			diesel::insert_into(validator_status_changes::table)
+				.values(chunk)
+				.on_conflict_do_nothing()
+				.execute(conn)?;
+		}
+
+		Ok(())
+	}
+}
+
+impl FromIterator<NewValidatorStatusChange> for NewValidatorStatusChanges {
+	fn from_iter<T: IntoIterator<Item = NewValidatorStatusChange>>(iter: T) -> Self {
+		let mut changes = vec![];
+		for c in iter {
+			changes.push(c);
+		}
+		NewValidatorStatusChanges(changes)
+	}
+}
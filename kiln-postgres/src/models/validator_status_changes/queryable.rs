@@ -0,0 +1,123 @@
+use diesel::{
+	ExpressionMethods, Identifiable, PgConnection, QueryDsl, QueryResult, Queryable, RunQueryDsl,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::schema::{
+	validator_status_changes,
+	validator_status_changes::dsl::validator_status_changes as dsl_validator_status_changes,
+};
+
+#[derive(Queryable, Identifiable)]
+#[primary_key(chain, validator_index, new_status)]
+#[table_name = "validator_status_changes"]
+struct DbValidatorStatusChange {
+	validator_index: i64,
+	epoch: i64,
+	old_status: Option<String>,
+	new_status: String,
+	chain: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ValidatorStatusChange {
+	validator_index: u64,
+	epoch: u64,
+	old_status: Option<String>,
+	new_status: String,
+	chain: String,
+}
+
+impl From<DbValidatorStatusChange> for ValidatorStatusChange {
+	fn from(db_change: DbValidatorStatusChange) -> Self {
+		ValidatorStatusChange {
+			validator_index: db_change.validator_index as u64,
+			epoch: db_change.epoch as u64,
+			old_status: db_change.old_status,
+			new_status: db_change.new_status,
+			chain: db_change.chain,
+		}
+	}
+}
+
+impl ValidatorStatusChange {
+	/// Return the index of the validator
+	pub fn validator_index(&self) -> u64 {
+		self.validator_index
+	}
+
+	/// Return the epoch at which the new status was observed
+	pub fn epoch(&self) -> u64 {
+		self.epoch
+	}
+
+	/// Return the status before the change, None if the validator was not known yet
+	pub fn old_status(&self) -> Option<&str> {
+		self.old_status.as_deref()
+	}
+
+	/// Return the status after the change
+	pub fn new_status(&self) -> &str {
+		&self.new_status
+	}
+
+	/// Return every status change of a validator, oldest first
+	pub fn list(
+		conn: &PgConnection,
+		chain: &str,
+		validator_index: u64,
+	) -> QueryResult<Vec<ValidatorStatusChange>> {
+		let db_changes: Vec<DbValidatorStatusChange> = dsl_validator_status_changes
+			.filter(validator_status_changes::chain.eq(chain))
+			.filter(validator_status_changes::validator_index.eq(validator_index as i64))
+			.order(validator_status_changes::epoch.asc())
+			.load(conn)?;
+
+		Ok(db_changes.into_iter().map(|c| c.into()).collect())
+	}
+
+	/// Return the changes to `status` observed between `from_epoch` and `to_epoch` included
+	///
+	/// e.g. `active_ongoing` for the activations, `exited_unslashed` for the exits
+	pub fn list_entering_status(
+		conn: &PgConnection,
+		chain: &str,
+		status: &str,
+		from_epoch: u64,
+		to_epoch: u64,
+	) -> QueryResult<Vec<ValidatorStatusChange>> {
+		let db_changes: Vec<DbValidatorStatusChange> = dsl_validator_status_changes
+			.filter(validator_status_changes::chain.eq(chain))
+			.filter(validator_status_changes::new_status.eq(status))
+			.filter(validator_status_changes::epoch.between(from_epoch as i64, to_epoch as i64))
+			.order((
+				validator_status_changes::epoch.asc(),
+				validator_status_changes::validator_index.asc(),
+			))
+			.load(conn)?;
+
+		Ok(db_changes.into_iter().map(|c| c.into()).collect())
+	}
+
+	/// Return the number of epochs a validator spent between entering `from_status` and
+	/// entering `to_status`
+	///
+	/// e.g. from `pending_queued` to `active_ongoing` for the activation delay.
+	/// Return None if one of the two statuses was never observed.
+	pub fn epochs_between(
+		conn: &PgConnection,
+		chain: &str,
+		validator_index: u64,
+		from_status: &str,
+		to_status: &str,
+	) -> QueryResult<Option<u64>> {
+		let changes = Self::list(conn, chain, validator_index)?;
+		let from = changes.iter().find(|c| c.new_status == from_status);
+		let to = changes.iter().find(|c| c.new_status == to_status);
+
+		Ok(match (from, to) {
+			(Some(from), Some(to)) => Some(to.epoch.saturating_sub(from.epoch)),
+			_ => None,
+		})
+	}
+}
@@ -1,11 +1,13 @@
+use std::collections::HashMap;
+
 use diesel::{
-	pg::upsert::excluded, ExpressionMethods, Insertable, PgConnection, QueryResult, RunQueryDsl,
+	pg::upsert::excluded, ExpressionMethods, Insertable, PgConnection, QueryDsl, QueryResult,
+	RunQueryDsl,
 };
 use eth2::types::ValidatorData;
-use primitive_types::H256;
 
 use crate::{
-	models::Hash256,
+	models::{Hash256, NewValidatorStatusChange, NewValidatorStatusChanges},
 	schema::{validators, validators::dsl::validators as dsl_validators},
 };
 
@@ -23,10 +25,12 @@ pub struct NewValidator {
 	activation_epoch: i64,
 	exit_epoch: i64,
 	withdrawable_epoch: i64,
+	chain: String,
 }
 
-impl From<ValidatorData> for NewValidator {
-	fn from(data: ValidatorData) -> Self {
+impl NewValidator {
+	/// Return a new insertable validator of `chain`
+	pub fn new(chain: &str, data: ValidatorData) -> NewValidator {
 		NewValidator {
 			index: data.index as i64,
 			balance: data.balance as i64,
@@ -40,25 +44,11 @@ impl From<ValidatorData> for NewValidator {
 			activation_epoch: data.validator.activation_epoch.as_u64() as i64,
 			exit_epoch: data.validator.exit_epoch.as_u64() as i64,
 			withdrawable_epoch: data.validator.withdrawable_epoch.as_u64() as i64,
+			chain: chain.to_string(),
 		}
 	}
 }
 
-impl NewValidator {
-	pub fn set_deposit_transaction(
-		conn: &PgConnection,
-		pubkey: String,
-		transaction: H256,
-	) -> QueryResult<usize> {
-		let transaction: Hash256 = transaction.into();
-
-		diesel::update(dsl_validators)
-			.filter(validators::pubkey.eq(pubkey))
-			.set(validators::deposit_transaction.eq(transaction))
-			.execute(conn)
-	}
-}
-
 /// An wrapper around an array fo validators
 pub struct NewValidators(Vec<NewValidator>);
 
@@ -71,7 +61,7 @@ impl NewValidators {
 		for chunk in self.0.chunks(1000) {
 			diesel::insert_into(validators::table)
 				.values(chunk)
-				.on_conflict(validators::index)
+				.on_conflict((validators::chain, validators::index))
 				.do_update()
 				.set((
 					validators::balance.eq(excluded(validators::balance)),
@@ -86,6 +76,42 @@ impl NewValidators {
 
 		Ok(())
 	}
+
+	/// Return the status changes between the validators stored in db and these ones
+	///
+	/// Validators not stored yet are returned with no old status.
+	/// Must be called before `batch_upsert` overwrites the stored statuses.
+	pub fn status_changes(
+		&self,
+		conn: &PgConnection,
+		chain: &str,
+		epoch: u64,
+	) -> QueryResult<NewValidatorStatusChanges> {
+		let stored_statuses: HashMap<i64, String> = dsl_validators
+			.filter(validators::chain.eq(chain))
+			.select((validators::index, validators::status))
+			.load::<(i64, String)>(conn)?
+			.into_iter()
+			.collect();
+
+		Ok(self
+			.0
+			.iter()
+			.filter_map(|v| {
+				let old_status = stored_statuses.get(&v.index);
+				if old_status == Some(&v.status) {
+					return None
+				}
+				Some(NewValidatorStatusChange::new(
+					chain,
+					v.index as u64,
+					epoch,
+					old_status.cloned(),
+					v.status.clone(),
+				))
+			})
+			.collect())
+	}
 }
 
 impl FromIterator<NewValidator> for NewValidators {
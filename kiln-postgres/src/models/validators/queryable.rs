@@ -1,17 +1,19 @@
-use diesel::{ExpressionMethods, Identifiable, PgConnection, QueryDsl, QueryResult, RunQueryDsl};
+use std::collections::HashSet;
+
+use diesel::{
+	sql_types::{Binary, Bool, Nullable, Text},
+	ExpressionMethods, Identifiable, PgConnection, QueryDsl, QueryResult, RunQueryDsl,
+};
 use primitive_types::{H160, H256};
 use serde::{Deserialize, Serialize};
 
 use crate::{
 	models::{Hash160, Hash256},
-	schema::{
-		transactions, transactions::dsl::transactions as dsl_transactions, validators,
-		validators::dsl::validators as dsl_validators,
-	},
+	schema::{validators, validators::dsl::validators as dsl_validators},
 };
 
 #[derive(Queryable, Identifiable)]
-#[primary_key(index)]
+#[primary_key(chain, index)]
 #[table_name = "validators"]
 struct DbValidator {
 	index: i64,
@@ -25,7 +27,13 @@ struct DbValidator {
 	activation_epoch: i64,
 	exit_epoch: i64,
 	withdrawable_epoch: i64,
-	deposit_transaction: Option<Hash256>,
+	chain: String,
+}
+
+#[derive(QueryableByName)]
+struct DbSlashedStatus {
+	#[sql_type = "Nullable<Bool>"]
+	slashed: Option<bool>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -41,7 +49,7 @@ pub struct Validator {
 	activation_epoch: u64,
 	exit_epoch: u64,
 	withdrawable_epoch: u64,
-	deposit_transaction: Option<H256>,
+	chain: String,
 }
 
 impl From<DbValidator> for Validator {
@@ -58,26 +66,46 @@ impl From<DbValidator> for Validator {
 			activation_epoch: db_validator.activation_epoch as u64,
 			exit_epoch: db_validator.exit_epoch as u64,
 			withdrawable_epoch: db_validator.withdrawable_epoch as u64,
-			deposit_transaction: db_validator.deposit_transaction.map(|t| t.into()),
+			chain: db_validator.chain,
 		}
 	}
 }
 
 impl Validator {
-	pub fn is_validator_slashed(conn: &PgConnection, address: H160) -> QueryResult<Option<bool>> {
+	/// Return the public keys of every validator of `chain`
+	pub fn list_pubkeys(conn: &PgConnection, chain: &str) -> QueryResult<HashSet<String>> {
+		let pubkeys: Vec<String> = dsl_validators
+			.filter(validators::chain.eq(chain))
+			.select(validators::pubkey)
+			.load(conn)?;
+
+		Ok(pubkeys.into_iter().collect())
+	}
+
+	/// Return whether one of the validators `address` deposited for has been slashed
+	///
+	/// Every depositor of a validator is accountable for it, whether it registered or topped it up.
+	/// Return None if `address` never deposited for a validator.
+	/// Only validators of `chain` are considered if provided.
+	pub fn is_validator_slashed(
+		conn: &PgConnection,
+		address: H160,
+		chain: Option<&str>,
+	) -> QueryResult<Option<bool>> {
 		let address: Hash160 = address.into();
 
-		let status: Vec<bool> = dsl_validators
-			.filter(validators::deposit_transaction.is_not_null())
-			.inner_join(dsl_transactions)
-			.filter(transactions::from.eq(address))
-			.select(validators::slashed)
-			.load(conn)?;
+		let status: DbSlashedStatus = diesel::sql_query(
+			r#"SELECT bool_or(v.slashed) AS slashed FROM validators v
+			WHERE EXISTS (
+				SELECT 1 FROM deposits d
+				JOIN transactions t ON t.hash = d.transaction_hash
+				WHERE d.chain = v.chain AND d.validator_index = v."index" AND t."from" = $1
+			) AND ($2::VARCHAR IS NULL OR v.chain = $2)"#,
+		)
+		.bind::<Binary, _>(address)
+		.bind::<Nullable<Text>, _>(chain)
+		.get_result(conn)?;
 
-		Ok(if status.is_empty() {
-			None
-		} else {
-			status.get(0).copied()
-		})
+		Ok(status.slashed)
 	}
 }
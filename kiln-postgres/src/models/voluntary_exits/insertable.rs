@@ -0,0 +1,69 @@
+use diesel::{ExpressionMethods, Insertable, PgConnection, QueryDsl, QueryResult, RunQueryDsl};
+
+use crate::schema::{
+	voluntary_exits, voluntary_exits::dsl::voluntary_exits as dsl_voluntary_exits,
+};
+
+/// An exit requested by a validator, included in the beacon block of `inclusion_slot`
+#[derive(Insertable)]
+#[table_name = "voluntary_exits"]
+pub struct NewVoluntaryExit {
+	validator_index: i64,
+	exit_epoch: i64,
+	inclusion_slot: i64,
+	chain: String,
+}
+
+impl NewVoluntaryExit {
+	/// Return a new insertable exit of a `chain` validator
+	pub fn new(
+		chain: &str,
+		validator_index: u64,
+		exit_epoch: u64,
+		inclusion_slot: u64,
+	) -> NewVoluntaryExit {
+		NewVoluntaryExit {
+			validator_index: validator_index as i64,
+			exit_epoch: exit_epoch as i64,
+			inclusion_slot: inclusion_slot as i64,
+			chain: chain.to_string(),
+		}
+	}
+
+	/// Delete the exits included in the block at `inclusion_slot`
+	///
+	/// Used when the block is replaced before finality
+	pub fn delete_at(conn: &PgConnection, chain: &str, inclusion_slot: u64) -> QueryResult<usize> {
+		diesel::delete(
+			dsl_voluntary_exits
+				.filter(voluntary_exits::chain.eq(chain))
+				.filter(voluntary_exits::inclusion_slot.eq(inclusion_slot as i64)),
+		)
+		.execute(conn)
+	}
+}
+
+/// An wrapper around an array of voluntary exits
+pub struct NewVoluntaryExits(Vec<NewVoluntaryExit>);
+
+impl NewVoluntaryExits {
+	/// Insert an array of exits in db
+	///
+	/// On conflict do nothing, a validator can only exit once
+	pub fn batch_insert(&self, conn: &PgConnection) -> QueryResult<usize> {
+		diesel::insert_into(voluntary_exits::table)
+			.values(&self.0)
+			.on_conflict_do_nothing()
+			.execute(conn)
+	}
+}
+
+impl FromIterator<NewVoluntaryExit> for NewVoluntaryExits {
+	fn from_iter<T: IntoIterator<Item = NewVoluntaryExit>>(iter: T) -> Self {
+		let mut exits = vec![];
+		for e in iter {
+			exits.push(e);
+		}
+		NewVoluntaryExits(exits)
+	}
+}
@@ -0,0 +1,113 @@
+use diesel::{
+	sql_types::{Binary, Nullable, Text},
+	ExpressionMethods, Identifiable, PgConnection, QueryDsl, QueryResult, Queryable, RunQueryDsl,
+};
+use primitive_types::H160;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	models::Hash160,
+	schema::{voluntary_exits, voluntary_exits::dsl::voluntary_exits as dsl_voluntary_exits},
+};
+
+#[derive(Queryable, QueryableByName, Identifiable)]
+#[primary_key(chain, validator_index)]
+#[table_name = "voluntary_exits"]
+struct DbVoluntaryExit {
+	validator_index: i64,
+	exit_epoch: i64,
+	inclusion_slot: i64,
+	chain: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct VoluntaryExit {
+	validator_index: u64,
+	exit_epoch: u64,
+	inclusion_slot: u64,
+	chain: String,
+}
+
+impl From<DbVoluntaryExit> for VoluntaryExit {
+	fn from(db_exit: DbVoluntaryExit) -> Self {
+		VoluntaryExit {
+			validator_index: db_exit.validator_index as u64,
+			exit_epoch: db_exit.exit_epoch as u64,
+			inclusion_slot: db_exit.inclusion_slot as u64,
+			chain: db_exit.chain,
+		}
+	}
+}
+
+impl VoluntaryExit {
+	/// Return the index of the exiting validator
+	pub fn validator_index(&self) -> u64 {
+		self.validator_index
+	}
+
+	/// Return the epoch from which the exit is valid
+	pub fn exit_epoch(&self) -> u64 {
+		self.exit_epoch
+	}
+
+	/// Return the slot of the block including the exit
+	pub fn inclusion_slot(&self) -> u64 {
+		self.inclusion_slot
+	}
+
+	/// Return the exit of a validator
+	pub fn get(
+		conn: &PgConnection,
+		chain: &str,
+		validator_index: u64,
+	) -> QueryResult<VoluntaryExit> {
+		let db_exit = dsl_voluntary_exits
+			.find((chain, validator_index as i64))
+			.first::<DbVoluntaryExit>(conn)?;
+
+		Ok(db_exit.into())
+	}
+
+	/// Return the exits of `chain` included between `from_slot` and `to_slot` included, oldest
+	/// first
+	pub fn list_between(
+		conn: &PgConnection,
+		chain: &str,
+		from_slot: u64,
+		to_slot: u64,
+	) -> QueryResult<Vec<VoluntaryExit>> {
+		let db_exits: Vec<DbVoluntaryExit> = dsl_voluntary_exits
+			.filter(voluntary_exits::chain.eq(chain))
+			.filter(voluntary_exits::inclusion_slot.between(from_slot as i64, to_slot as i64))
+			.order(voluntary_exits::inclusion_slot.asc())
+			.load(conn)?;
+
+		Ok(db_exits.into_iter().map(|e| e.into()).collect())
+	}
+
+	/// Return the exits of the validators `address` deposited for, oldest first
+	///
+	/// Only validators of `chain` are considered if provided.
+	pub fn list_from_depositor(
+		conn: &PgConnection,
+		address: H160,
+		chain: Option<&str>,
+	) -> QueryResult<Vec<VoluntaryExit>> {
+		let address: Hash160 = address.into();
+
+		let db_exits: Vec<DbVoluntaryExit> = diesel::sql_query(
+			r#"SELECT e.* FROM voluntary_exits e
+			WHERE EXISTS (
+				SELECT 1 FROM deposits d
+				JOIN transactions t ON t.hash = d.transaction_hash
+				WHERE d.chain = e.chain AND d.validator_index = e.validator_index AND t."from" = $1
+			) AND ($2::VARCHAR IS NULL OR e.chain = $2)
+			ORDER BY e.inclusion_slot ASC"#,
+		)
+		.bind::<Binary, _>(address)
+		.bind::<Nullable<Text>, _>(chain)
+		.load(conn)?;
+
+		Ok(db_exits.into_iter().map(|e| e.into()).collect())
+	}
+}
@@ -1,3 +1,94 @@
+table! {
+	attestation_duties (chain, validator_index, epoch) {
+		validator_index -> Int8,
+		epoch -> Int8,
+		slot -> Int8,
+		committee_index -> Int8,
+		chain -> Varchar,
+	}
+}
+
+table! {
+	attestation_inclusions (chain, validator_index, epoch, inclusion_slot) {
+		validator_index -> Int8,
+		epoch -> Int8,
+		inclusion_slot -> Int8,
+		beacon_block_root -> Bytea,
+		source_epoch -> Int8,
+		source_root -> Bytea,
+		target_epoch -> Int8,
+		target_root -> Bytea,
+		chain -> Varchar,
+	}
+}
+
+table! {
+	consensus_deposits (chain, deposit_index) {
+		deposit_index -> Int8,
+		inclusion_slot -> Int8,
+		pubkey -> Varchar,
+		withdrawal_credentials -> Bytea,
+		amount -> Int8,
+		signature -> Bytea,
+		chain -> Varchar,
+	}
+}
+
+table! {
+	contracts (chain, address, creation_transaction) {
+		address -> Bytea,
+		creator -> Bytea,
+		creation_transaction -> Bytea,
+		block_number -> Int8,
+		code_hash -> Bytea,
+		bytecode -> Bytea,
+		chain -> Varchar,
+	}
+}
+
+table! {
+	decoded_calls (transaction_hash) {
+		transaction_hash -> Bytea,
+		contract -> Bytea,
+		function_name -> Varchar,
+		signature -> Varchar,
+		params -> Jsonb,
+		chain -> Varchar,
+	}
+}
+
+table! {
+	decoded_events (transaction_hash, log_index) {
+		transaction_hash -> Bytea,
+		log_index -> Int8,
+		contract -> Bytea,
+		event_name -> Varchar,
+		signature -> Varchar,
+		params -> Jsonb,
+		chain -> Varchar,
+	}
+}
+
+table! {
+	deposit_reconciliations (chain, deposit_index) {
+		transaction_hash -> Bytea,
+		deposit_index -> Int8,
+		status -> Varchar,
+		chain -> Varchar,
+	}
+}
+
+table! {
+	deposits (id) {
+		id -> Int8,
+		deposit_index -> Nullable<Int8>,
+		validator_index -> Int8,
+		transaction_hash -> Bytea,
+		amount -> Nullable<Int8>,
+		chain -> Varchar,
+	}
+}
+
 table! {
 	execution_blocks (hash) {
 		hash -> Bytea,
@@ -6,14 +97,125 @@ table! {
 		state_root -> Bytea,
 		transactions_root -> Bytea,
 		receipts_root -> Bytea,
+		chain -> Varchar,
+		timestamp -> Nullable<Int8>,
+		fee_recipient -> Nullable<Bytea>,
+		gas_limit -> Nullable<Int8>,
+		gas_used -> Nullable<Int8>,
+		base_fee_per_gas -> Nullable<Bytea>,
+		extra_data -> Nullable<Bytea>,
+		logs_bloom -> Nullable<Bytea>,
+		prev_randao -> Nullable<Bytea>,
+	}
+}
+
+table! {
+	execution_deposits (chain, deposit_index) {
+		deposit_index -> Int8,
+		transaction_hash -> Bytea,
+		log_index -> Int8,
+		pubkey -> Varchar,
+		withdrawal_credentials -> Bytea,
+		amount -> Int8,
+		signature -> Bytea,
+		chain -> Varchar,
+	}
+}
+
+table! {
+	failed_heights (chain, syncer, height) {
+		syncer -> Varchar,
+		height -> Int8,
+		attempts -> Int4,
+		error -> Varchar,
+		chain -> Varchar,
+	}
+}
+
+table! {
+	internal_calls (transaction_hash, position) {
+		transaction_hash -> Bytea,
+		position -> Int8,
+		depth -> Int4,
+		call_type -> Varchar,
+		from -> Bytea,
+		to -> Nullable<Bytea>,
+		value -> Bytea,
+		error -> Nullable<Varchar>,
+		chain -> Varchar,
+	}
+}
+
+table! {
+	logs (transaction_hash, log_index) {
+		transaction_hash -> Bytea,
+		log_index -> Int8,
+		address -> Bytea,
+		topic0 -> Nullable<Bytea>,
+		topic1 -> Nullable<Bytea>,
+		topic2 -> Nullable<Bytea>,
+		topic3 -> Nullable<Bytea>,
+		data -> Bytea,
+		chain -> Varchar,
+	}
+}
+
+table! {
+	slashings (chain, validator_index, slot, slashing_type) {
+		validator_index -> Int8,
+		slot -> Int8,
+		slashing_type -> Varchar,
+		whistleblower_index -> Int8,
+		chain -> Varchar,
 	}
 }
 
 table! {
-	slots (height) {
+	slots (chain, height) {
 		height -> Int8,
 		block_hash -> Nullable<Bytea>,
 		block_number -> Nullable<Int8>,
+		finalized -> Nullable<Bool>,
+		missed -> Bool,
+		chain -> Varchar,
+		block_root -> Nullable<Bytea>,
+		proposer_index -> Nullable<Int8>,
+		graffiti -> Nullable<Varchar>,
+		randao_reveal -> Nullable<Bytea>,
+	}
+}
+
+table! {
+	sync_committee_members (chain, period, position) {
+		period -> Int8,
+		position -> Int4,
+		validator_index -> Int8,
+		chain -> Varchar,
+	}
+}
+
+table! {
+	sync_participations (chain, slot, position) {
+		slot -> Int8,
+		position -> Int4,
+		validator_index -> Int8,
+		participated -> Bool,
+		chain -> Varchar,
+	}
+}
+
+table! {
+	token_transfers (transaction_hash, log_index, batch_index) {
+		transaction_hash -> Bytea,
+		log_index -> Int8,
+		batch_index -> Int4,
+		token -> Bytea,
+		standard -> Varchar,
+		from -> Bytea,
+		to -> Bytea,
+		token_id -> Nullable<Bytea>,
+		value -> Bytea,
+		chain -> Varchar,
 	}
 }
 
@@ -27,11 +229,43 @@ table! {
 		input -> Bytea,
 		value -> Bytea,
 		status -> Nullable<Bool>,
+		chain -> Varchar,
+		gas_used -> Nullable<Int8>,
+		cumulative_gas_used -> Nullable<Int8>,
+		effective_gas_price -> Nullable<Bytea>,
+		contract_address -> Nullable<Bytea>,
+		nonce -> Nullable<Int8>,
+		gas -> Nullable<Int8>,
+		gas_price -> Nullable<Bytea>,
+		max_fee_per_gas -> Nullable<Bytea>,
+		max_priority_fee_per_gas -> Nullable<Bytea>,
+		transaction_type -> Nullable<Int4>,
+		access_list -> Nullable<Jsonb>,
+	}
+}
+
+table! {
+	validator_balances (chain, validator_index, epoch) {
+		validator_index -> Int8,
+		epoch -> Int8,
+		balance -> Int8,
+		effective_balance -> Int8,
+		chain -> Varchar,
+	}
+}
+
+table! {
+	validator_status_changes (chain, validator_index, new_status) {
+		validator_index -> Int8,
+		epoch -> Int8,
+		old_status -> Nullable<Varchar>,
+		new_status -> Varchar,
+		chain -> Varchar,
 	}
 }
 
 table! {
-	validators (index) {
+	validators (chain, index) {
 		index -> Int8,
 		balance -> Int8,
 		status -> Varchar,
@@ -43,11 +277,50 @@ table! {
 		activation_epoch -> Int8,
 		exit_epoch -> Int8,
 		withdrawable_epoch -> Int8,
-		deposit_transaction -> Nullable<Bytea>,
+		chain -> Varchar,
+	}
+}
+
+table! {
+	voluntary_exits (chain, validator_index) {
+		validator_index -> Int8,
+		exit_epoch -> Int8,
+		inclusion_slot -> Int8,
+		chain -> Varchar,
 	}
 }
 
+joinable!(contracts -> transactions (creation_transaction));
+joinable!(decoded_calls -> transactions (transaction_hash));
+joinable!(decoded_events -> transactions (transaction_hash));
+joinable!(deposits -> transactions (transaction_hash));
+joinable!(internal_calls -> transactions (transaction_hash));
+joinable!(logs -> transactions (transaction_hash));
+joinable!(token_transfers -> transactions (transaction_hash));
 joinable!(transactions -> execution_blocks (block_hash));
-joinable!(validators -> transactions (deposit_transaction));
 
-allow_tables_to_appear_in_same_query!(execution_blocks, slots, transactions, validators,);
+allow_tables_to_appear_in_same_query!(
+	attestation_duties,
+	attestation_inclusions,
+	consensus_deposits,
+	contracts,
+	decoded_calls,
+	decoded_events,
+	deposit_reconciliations,
+	deposits,
+	execution_blocks,
+	execution_deposits,
+	failed_heights,
+	internal_calls,
+	logs,
+	slashings,
+	slots,
+	sync_committee_members,
+	sync_participations,
+	token_transfers,
+	transactions,
+	validator_balances,
+	validator_status_changes,
+	validators,
+	voluntary_exits,
+);
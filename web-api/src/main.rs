@@ -20,6 +20,12 @@ fn rocket() -> _ {
 
 	rocket::build().attach(PgConn::fairing()).mount(
 		"/",
-		routes![routes::nfts_by_address, routes::list_all_eligible_nft],
+		routes![
+			routes::nfts_by_address,
+			routes::list_all_eligible_nft,
+			routes::slashings_by_address,
+			routes::exits_by_address,
+			routes::token_transfers_by_address,
+		],
 	)
 }
@@ -1,3 +1,7 @@
 mod packed_nft;
+mod tokens;
+mod validators;
 
 pub(crate) use packed_nft::*;
+pub(crate) use tokens::*;
+pub(crate) use validators::*;
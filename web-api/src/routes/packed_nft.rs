@@ -1,8 +1,11 @@
-use std::{collections::HashMap, ops::Deref};
+use std::{
+	collections::{HashMap, HashSet},
+	ops::Deref,
+};
 
-use kiln_postgres::{Transaction, Validator};
+use kiln_postgres::{Contract, Transaction, Validator};
 use log::info;
-use primitive_types::H160;
+use primitive_types::{H160, H256};
 use rocket::{get, serde::json::Json};
 use rocket_sync_db_pools::{
 	diesel::{self, r2d2::ConnectionManager, PgConnection},
@@ -13,12 +16,17 @@ use serde::Serialize;
 use crate::{packed_nft_types::PackedNftTypes, params::Hash160, Error, PgConn};
 
 /// Return the packed list of NFTs this address is eligible to mint
-#[get("/address/<address>/nfts")]
+///
+/// Only activity on `chain` is considered if provided
+#[get("/address/<address>/nfts?<chain>")]
 pub async fn nfts_by_address(
 	conn: PgConn,
 	address: Hash160,
+	chain: Option<String>,
 ) -> Result<Json<PackedNftTypes>, Error> {
-	let packed_nft = conn.run(move |c| inner_get_packed_nft(c, address.into())).await?;
+	let packed_nft = conn
+		.run(move |c| inner_get_packed_nft(c, address.into(), chain.as_deref()))
+		.await?;
 
 	Ok(Json(packed_nft))
 }
@@ -29,11 +37,20 @@ pub struct AddressNftPair {
 	nft: PackedNftTypes,
 }
 
-#[get("/nfts")]
-pub async fn list_all_eligible_nft(conn: PgConn) -> Result<Json<Vec<AddressNftPair>>, Error> {
+/// Return the packed list of NFTs of every address eligible to mint
+///
+/// Only activity on `chain` is considered if provided
+#[get("/nfts?<chain>")]
+pub async fn list_all_eligible_nft(
+	conn: PgConn,
+	chain: Option<String>,
+) -> Result<Json<Vec<AddressNftPair>>, Error> {
 	use rayon::prelude::*;
 
-	let issuers = conn.run(move |c| Transaction::list_all_distinct_issuer(c)).await?;
+	let issuer_chain = chain.clone();
+	let issuers = conn
+		.run(move |c| Transaction::list_all_distinct_issuer(c, issuer_chain.as_deref()))
+		.await?;
 	info!("{} issuers to query", issuers.len());
 
 	let manager = ConnectionManager::<PgConnection>::new(std::env::var("DATABASE_URL").unwrap());
@@ -44,9 +61,11 @@ pub async fn list_all_eligible_nft(conn: PgConn) -> Result<Json<Vec<AddressNftPa
 		.map(|issuer| {
 			let pool = pool.clone();
 			let connection = pool.get().unwrap();
-			inner_get_packed_nft(connection.deref(), *issuer).map(|r| AddressNftPair {
-				address: *issuer,
-				nft: r,
+			inner_get_packed_nft(connection.deref(), *issuer, chain.as_deref()).map(|r| {
+				AddressNftPair {
+					address: *issuer,
+					nft: r,
+				}
 			})
 		})
 		.collect::<Result<Vec<AddressNftPair>, Error>>()?;
@@ -57,13 +76,14 @@ pub async fn list_all_eligible_nft(conn: PgConn) -> Result<Json<Vec<AddressNftPa
 fn inner_get_packed_nft(
 	conn: &diesel::PgConnection,
 	address: H160,
+	chain: Option<&str>,
 ) -> Result<PackedNftTypes, Error> {
 	let mut packed_nfts = PackedNftTypes::zero();
 
 	// Get the address transaction
-	let transactions = Transaction::list_all_from_address(conn, address)?;
+	let transactions = Transaction::list_all_from_address(conn, address, chain)?;
 
-	let opt_validator_slashed = Validator::is_validator_slashed(conn, address)?;
+	let opt_validator_slashed = Validator::is_validator_slashed(conn, address, chain)?;
 	if let Some(slashed) = opt_validator_slashed {
 		// is validator
 		packed_nfts.set_become_validator();
@@ -83,13 +103,23 @@ fn inner_get_packed_nft(
 		packed_nfts.set_do_one_transaction();
 	}
 
+	// Count the successful deployments, each contract once per creation transaction
+	let mut deployments: HashSet<(H160, H256)> = Contract::list_deployed_by(conn, address, chain)?
+		.iter()
+		.map(|c| (c.address(), c.creation_transaction()))
+		.collect();
+
 	// Loop over transactions
-	// count deployed contracts and calls to smart contracts
-	let mut deployed_contracts: usize = 0;
+	// count deployments missing from the contracts and calls to smart contracts
 	let mut call_count_by_contract: HashMap<H160, usize> = HashMap::new();
 	for t in transactions.into_iter() {
 		if t.to().is_none() {
-			deployed_contracts += 1;
+			// Blocks synced before the contracts were recorded only have the transaction
+			if t.status() != Some(false) {
+				if let Some(contract) = t.contract_address() {
+					deployments.insert((contract, t.hash()));
+				}
+			}
 			continue
 		}
 
@@ -99,6 +129,7 @@ fn inner_get_packed_nft(
 		}
 	}
 
+	let deployed_contracts = deployments.len();
 	// deploy 1 contract
 	if deployed_contracts > 0 {
 		packed_nfts.set_deploy_contract();
@@ -0,0 +1,35 @@
+use kiln_postgres::TokenTransfer;
+use rocket::{get, serde::json::Json};
+
+use crate::{params::Hash160, Error, PgConn};
+
+/// Number of logs whose transfers are returned when no limit is requested
+const DEFAULT_LIMIT: u64 = 100;
+/// Maximum number of logs whose transfers can be returned at once
+const MAX_LIMIT: u64 = 1000;
+
+/// Return the ERC-20, ERC-721 and ERC-1155 transfers sent or received by this address
+///
+/// Transfers are paginated in chain order: the next page starts after the `block_number` and
+/// `log_index` of the last transfer, passed as `after_block` and `after_log`.
+/// `limit` bounds the number of logs whose transfers are returned, up to 1000.
+/// Only transfers of `chain` are considered if provided
+#[get("/address/<address>/token_transfers?<chain>&<limit>&<after_block>&<after_log>")]
+pub async fn token_transfers_by_address(
+	conn: PgConn,
+	address: Hash160,
+	chain: Option<String>,
+	limit: Option<u64>,
+	after_block: Option<u64>,
+	after_log: Option<u64>,
+) -> Result<Json<Vec<TokenTransfer>>, Error> {
+	let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT);
+	let after = after_block.zip(after_log);
+	let transfers = conn
+		.run(move |c| {
+			TokenTransfer::list_for_address(c, address.into(), chain.as_deref(), after, limit)
+		})
+		.await?;
+
+	Ok(Json(transfers))
+}
@@ -0,0 +1,36 @@
+use kiln_postgres::{Slashing, VoluntaryExit};
+use rocket::{get, serde::json::Json};
+
+use crate::{params::Hash160, Error, PgConn};
+
+/// Return the slashing evidence of the validators registered by this address
+///
+/// Only validators of `chain` are considered if provided
+#[get("/address/<address>/slashings?<chain>")]
+pub async fn slashings_by_address(
+	conn: PgConn,
+	address: Hash160,
+	chain: Option<String>,
+) -> Result<Json<Vec<Slashing>>, Error> {
+	let slashings = conn
+		.run(move |c| Slashing::list_from_depositor(c, address.into(), chain.as_deref()))
+		.await?;
+
+	Ok(Json(slashings))
+}
+
+/// Return the voluntary exits of the validators registered by this address
+///
+/// Only validators of `chain` are considered if provided
+#[get("/address/<address>/exits?<chain>")]
+pub async fn exits_by_address(
+	conn: PgConn,
+	address: Hash160,
+	chain: Option<String>,
+) -> Result<Json<Vec<VoluntaryExit>>, Error> {
+	let exits = conn
+		.run(move |c| VoluntaryExit::list_from_depositor(c, address.into(), chain.as_deref()))
+		.await?;
+
+	Ok(Json(exits))
+}